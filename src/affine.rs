@@ -0,0 +1,98 @@
+use crate::as_rgba::Float;
+
+
+/// 2D affine transformation.  
+/// 
+/// `[[a, b, c], [d, e, f]]` maps (x, y) to (a * x + b * y + c, d * x + e * y + f).  
+/// The y axis points down, so a positive rotation is clockwise on screen.
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::Affine;
+/// 
+/// // Scale, rotate by 90 degrees and move.
+/// let transform = Affine::scaling(2., 2.)
+///     .then(Affine::rotation(std::f32::consts::FRAC_PI_2 as _))
+///     .then(Affine::translation(10.5, 0.));
+/// 
+/// let (x, y) = transform.apply(1., 0.);
+/// assert!((x - 10.5).abs() < 1e-5 && (y - 2.).abs() < 1e-5);
+/// ``````
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Affine {
+    pub matrix: [[Float; 3]; 2],
+}
+
+impl Affine {
+
+    pub const IDENTITY: Self = Self::new([
+        [1., 0., 0.],
+        [0., 1., 0.],
+    ]);
+
+    pub const fn new(matrix: [[Float; 3]; 2]) -> Self {
+        Self { matrix }
+    }
+
+    pub const fn translation(x: Float, y: Float) -> Self {
+        Self::new([
+            [1., 0., x],
+            [0., 1., y],
+        ])
+    }
+
+    pub const fn scaling(x: Float, y: Float) -> Self {
+        Self::new([
+            [x, 0., 0.],
+            [0., y, 0.],
+        ])
+    }
+
+    /// Rotation around the origin in radians.
+    pub fn rotation(radians: Float) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self::new([
+            [cos, -sin, 0.],
+            [sin, cos, 0.],
+        ])
+    }
+
+    /// Returns the transformation which applies `self` and then `next`.
+    pub fn then(self, next: Self) -> Self {
+        let [[a, b, c], [d, e, f]] = self.matrix;
+        let [[na, nb, nc], [nd, ne, nf]] = next.matrix;
+        Self::new([
+            [na * a + nb * d, na * b + nb * e, na * c + nb * f + nc],
+            [nd * a + ne * d, nd * b + ne * e, nd * c + ne * f + nf],
+        ])
+    }
+
+    /// Returns `None` if the transformation is not invertible.
+    pub fn inverse(self) -> Option<Self> {
+        let [[a, b, c], [d, e, f]] = self.matrix;
+        let det = a * e - b * d;
+        if det == 0. || !det.is_finite() {
+            return None;
+        }
+
+        Some(Self::new([
+            [e / det, -b / det, (b * f - c * e) / det],
+            [-d / det, a / det, (c * d - a * f) / det],
+        ]))
+    }
+
+    pub fn apply(self, x: Float, y: Float) -> (Float, Float) {
+        let [[a, b, c], [d, e, f]] = self.matrix;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+}
+
+impl Default for Affine {
+
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
@@ -0,0 +1,12 @@
+/// Alignment of the top image on one axis of the bottom image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Alignment {
+
+    /// Left or top.
+    Start,
+
+    Center,
+
+    /// Right or bottom.
+    End,
+}
@@ -0,0 +1,301 @@
+use image::{GenericImageView, ImageBuffer, Rgba};
+use crate::{as_rgba::Float, AsRgba};
+
+
+/// Coverage of an image, used to derive layer effects.
+#[derive(Debug, Clone)]
+pub(crate) struct AlphaMask {
+    pub width: u32,
+    pub height: u32,
+    pub values: Vec<Float>,
+}
+
+impl AlphaMask {
+
+    /// Takes the alpha of the image, surrounded by a transparent margin of `padding` pixels.
+    pub fn from_image<I>(image: &I, padding: u32) -> Self
+    where 
+        I: GenericImageView::<Pixel: AsRgba>,
+    {
+        let (image_width, image_height) = image.dimensions();
+        let width = image_width.saturating_add(padding.saturating_mul(2));
+        let height = image_height.saturating_add(padding.saturating_mul(2));
+
+        let mut values = vec![0.; (width as usize).saturating_mul(height as usize)];
+        for (x, y, pixel) in image.pixels() {
+            let index = (y + padding) as usize * width as usize + (x + padding) as usize;
+            values[index] = AsRgba::to_rgba(&pixel)[3];
+        }
+
+        Self { width, height, values }
+    }
+
+    /// Expands the shape by `radius` pixels, keeping its edge antialiased.
+    pub fn dilate(&mut self, radius: Float) {
+        if radius <= 0. {
+            return;
+        }
+
+        let distance = self.signed_distance();
+        for (value, d) in self.values.iter_mut().zip(distance) {
+            *value = value.max((radius + 0.5 - d).clamp(0., 1.));
+        }
+    }
+
+    /// Applies a Gaussian blur whose standard deviation is `sigma`.  
+    /// Pixels outside the mask are treated as transparent.
+    pub fn blur(&mut self, sigma: Float) {
+        if sigma <= 0. {
+            return;
+        }
+
+        let kernel = gaussian_kernel(sigma);
+        let (width, height) = (self.width as usize, self.height as usize);
+
+        let mut tmp = vec![0.; self.values.len()];
+        convolve(&self.values, &mut tmp, &kernel, width, height, 1, width);
+        convolve(&tmp, &mut self.values, &kernel, height, width, width, 1);
+    }
+
+    /// Returns the distance from the center of each pixel to the edge, where the alpha crosses 0.5.  
+    /// It is positive outside and negative inside.
+    pub fn signed_distance(&self) -> Vec<Float> {
+        let is_inside: fn(Float) -> bool = |alpha| alpha > 0.;
+        let is_outside: fn(Float) -> bool = |alpha| alpha < 1.;
+        let outside = self.nearest(is_inside);
+        let inside = self.nearest(is_outside);
+
+        let width = self.width as usize;
+        let coordinate = |index: usize| ((index % width) as Float, (index / width) as Float);
+
+        // Distance to the edge crossing the nearest feature pixel or the feature pixels next to it.
+        let distance_to_edge = |index: usize, (distance, nearest): (Float, usize), is_feature: fn(Float) -> bool| {
+            if distance.is_infinite() {
+                return distance;
+            }
+
+            let (x, y) = coordinate(index);
+            let (nearest_x, nearest_y) = coordinate(nearest);
+            let mut min = Float::INFINITY;
+            for (dx, dy) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx as Float, dy as Float))) {
+                let (edge_x, edge_y) = (nearest_x + dx, nearest_y + dy);
+                if edge_x < 0. || edge_y < 0. || edge_x >= self.width as Float || edge_y >= self.height as Float {
+                    continue;
+                }
+                let edge = edge_y as usize * width + edge_x as usize;
+                if !is_feature(self.values[edge]) {
+                    continue;
+                }
+
+                let (offset, normal) = self.edge(edge);
+                if let Some((normal_x, normal_y)) = normal {
+                    min = min.min((x - (edge_x + normal_x * offset)).hypot(y - (edge_y + normal_y * offset)));
+                }
+            }
+
+            // The direction of the edge is unknown, such as for a single pixel.
+            if min.is_infinite() {
+                return (distance - self.edge(nearest).0.abs()).max(0.);
+            }
+            min
+        };
+
+        (0..self.values.len())
+            .map(|index| {
+                let alpha = self.values[index];
+                if alpha <= 0. {
+                    distance_to_edge(index, outside[index], is_inside)
+                }
+                else if alpha >= 1. {
+                    -distance_to_edge(index, inside[index], is_outside)
+                }
+                else {
+                    self.edge(index).0
+                }
+            })
+            .collect()
+    }
+
+    /// Estimates the edge crossing a pixel from its alpha and the gradient around it.  
+    /// 
+    /// Returns the signed distance from the center to the edge, and the normal pointing inside if the direction is known.
+    /// 
+    /// # References
+    /// Gustavson, S., & Strand, R. (2011). Anti-aliased Euclidean distance transform.
+    fn edge(&self, index: usize) -> (Float, Option<(Float, Float)>) {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (x, y) = ((index as i64) % width, (index as i64) / width);
+        let alpha = |dx: i64, dy: i64| {
+            let (x, y) = (x + dx, y + dy);
+            if x < 0 || y < 0 || x >= width || y >= height {
+                return 0.;
+            }
+            self.values[(y * width + x) as usize]
+        };
+
+        // Sobel operator.
+        let gx = (alpha(1, -1) + 2. * alpha(1, 0) + alpha(1, 1)) - (alpha(-1, -1) + 2. * alpha(-1, 0) + alpha(-1, 1));
+        let gy = (alpha(-1, 1) + 2. * alpha(0, 1) + alpha(1, 1)) - (alpha(-1, -1) + 2. * alpha(0, -1) + alpha(1, -1));
+        let length = gx.hypot(gy);
+
+        let a = alpha(0, 0);
+        if length <= 0. {
+            return (0.5 - a, None);
+        }
+
+        let normal = (gx / length, gy / length);
+        let (gx, gy) = (normal.0.abs(), normal.1.abs());
+        let (gx, gy) = if gx < gy { (gy, gx) } else { (gx, gy) };
+
+        let a1 = 0.5 * gy / gx;
+        let offset = if a < a1 {
+            0.5 * (gx + gy) - (2. * gx * gy * a).sqrt()
+        }
+        else if a < 1. - a1 {
+            (0.5 - a) * gx
+        }
+        else {
+            -0.5 * (gx + gy) + (2. * gx * gy * (1. - a)).sqrt()
+        };
+
+        (offset, Some(normal))
+    }
+
+    /// Returns the Euclidean distance from the center of each pixel to the center of the nearest pixel 
+    /// that satisfies `is_feature`, and the index of that pixel.  
+    /// The distance is infinite if there are no such pixels.
+    fn nearest(&self, is_feature: impl Fn(Float) -> bool) -> Vec<(Float, usize)> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut rows: Vec<(Float, usize)> = self.values.iter()
+            .map(|&alpha| if is_feature(alpha) { 0. } else { Float::INFINITY })
+            .map(|f| (f, 0))
+            .collect();
+
+        // The squared distance to the nearest feature in the same row, and its column.
+        let mut f = Vec::with_capacity(width.max(height));
+        let mut out = vec![(0., 0); width.max(height)];
+        for row in rows.chunks_exact_mut(width) {
+            f.clear();
+            f.extend(row.iter().map(|(f, _)| *f));
+            distance_transform_1d(&f, &mut out[..width]);
+            row.copy_from_slice(&out[..width]);
+        }
+
+        // Then the nearest of them in the same column.
+        let mut nearest = vec![(Float::INFINITY, 0); rows.len()];
+        for x in 0..width {
+            f.clear();
+            f.extend((0..height).map(|y| rows[y * width + x].0));
+            distance_transform_1d(&f, &mut out[..height]);
+            for (y, (squared, nearest_y)) in out[..height].iter().enumerate() {
+                let nearest_x = rows[nearest_y * width + x].1;
+                nearest[y * width + x] = (squared.sqrt(), nearest_y * width + nearest_x);
+            }
+        }
+
+        nearest
+    }
+
+    /// Creates an image of the color whose alpha is the mask.
+    pub fn to_image(&self, [r, g, b]: [Float; 3], premultiplied: bool) -> ImageBuffer<Rgba<Float>, Vec<Float>> {
+        ImageBuffer::from_fn(self.width, self.height, |x, y| {
+            let a = self.values[y as usize * self.width as usize + x as usize].clamp(0., 1.);
+            if premultiplied {
+                Rgba([r * a, g * a, b * a, a])
+            }
+            else {
+                Rgba([r, g, b, a])
+            }
+        })
+    }
+}
+
+/// Squared distance transform of sampled functions.  
+/// `out` receives the squared distance and the index of the nearest sample.
+/// 
+/// # References
+/// Felzenszwalb, P. F., & Huttenlocher, D. P. (2012). Distance Transforms of Sampled Functions.
+fn distance_transform_1d(f: &[Float], out: &mut [(Float, usize)]) {
+    let n = f.len();
+    if n == 0 {
+        return;
+    }
+
+    // Parabolas of the lower envelope and the boundaries between them.
+    let mut vertices = vec![0; n];
+    let mut boundaries = vec![0.; n + 1];
+    let mut k = 0;
+    boundaries[0] = Float::NEG_INFINITY;
+    boundaries[1] = Float::INFINITY;
+
+    let intersection = |q: usize, p: usize| {
+        let (q_f, p_f) = (q as Float, p as Float);
+        ((f[q] + q_f * q_f) - (f[p] + p_f * p_f)) / (2. * q_f - 2. * p_f)
+    };
+
+    for q in 1..n {
+        if f[q].is_infinite() {
+            continue;
+        }
+        if f[vertices[k]].is_infinite() {
+            vertices[k] = q;
+            continue;
+        }
+
+        let mut s = intersection(q, vertices[k]);
+        while s <= boundaries[k] {
+            k -= 1;
+            s = intersection(q, vertices[k]);
+        }
+        k += 1;
+        vertices[k] = q;
+        boundaries[k] = s;
+        boundaries[k + 1] = Float::INFINITY;
+    }
+
+    k = 0;
+    for (q, out) in out.iter_mut().enumerate() {
+        while boundaries[k + 1] < q as Float {
+            k += 1;
+        }
+        let d = q as Float - vertices[k] as Float;
+        *out = (d * d + f[vertices[k]], vertices[k]);
+    }
+}
+
+/// Returns the radius that covers the blur of `sigma`, saturating at `u32::MAX`.
+pub(crate) fn blur_radius(sigma: Float) -> u32 {
+    if sigma.is_nan() || sigma <= 0. {
+        return 0;
+    }
+    // The cast saturates.
+    (sigma * 3.).ceil() as u32
+}
+
+fn gaussian_kernel(sigma: Float) -> Vec<Float> {
+    let radius = blur_radius(sigma) as i64;
+    let kernel: Vec<Float> = (-radius..=radius)
+        .map(|i| (-((i * i) as Float) / (2. * sigma * sigma)).exp())
+        .collect();
+
+    let sum: Float = kernel.iter().sum();
+    kernel.into_iter().map(|w| w / sum).collect()
+}
+
+/// Convolves every line of `src` with `kernel`.  
+/// A line has `len` values separated by `step`, and lines start `stride` apart.
+fn convolve(src: &[Float], dst: &mut [Float], kernel: &[Float], len: usize, lines: usize, step: usize, stride: usize) {
+    let radius = kernel.len() / 2;
+    for line in 0..lines {
+        let start = line * stride;
+        for i in 0..len {
+            let from = i.saturating_sub(radius);
+            let to = (i + radius).min(len - 1);
+            let mut sum = 0.;
+            for j in from..=to {
+                sum += src[start + j * step] * kernel[j + radius - i];
+            }
+            dst[start + i * step] = sum;
+        }
+    }
+}
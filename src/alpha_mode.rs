@@ -0,0 +1,18 @@
+/// How the color channels of the images relate to the alpha channel.  
+/// 
+/// With `Premultiplied`, the values returned by [`AsRgba::to_rgba`](crate::AsRgba::to_rgba) are read as
+/// premultiplied colors, and the results are written back premultiplied.
+/// Compositing then runs natively in premultiplied space, so no un-premultiplying round trip is needed.  
+/// It applies to both the bottom and the top image.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AlphaMode {
+    /// The color channels are independent of the alpha channel.
+    #[default]
+    Straight,
+
+    /// The color channels are already multiplied by the alpha channel.
+    Premultiplied,
+}
@@ -0,0 +1,40 @@
+use crate::alignment::Alignment;
+
+
+/// Point of the bottom image that the top image is aligned to.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+
+    #[default]
+    Center,
+
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+
+    /// Returns the alignment on the horizontal and vertical axes.
+    pub(crate) const fn alignment(self) -> (Alignment, Alignment) {
+        match self {
+            Anchor::TopLeft => (Alignment::Start, Alignment::Start),
+            Anchor::Top => (Alignment::Center, Alignment::Start),
+            Anchor::TopRight => (Alignment::End, Alignment::Start),
+            Anchor::Left => (Alignment::Start, Alignment::Center),
+            Anchor::Center => (Alignment::Center, Alignment::Center),
+            Anchor::Right => (Alignment::End, Alignment::Center),
+            Anchor::BottomLeft => (Alignment::Start, Alignment::End),
+            Anchor::Bottom => (Alignment::Center, Alignment::End),
+            Anchor::BottomRight => (Alignment::End, Alignment::End),
+        }
+    }
+}
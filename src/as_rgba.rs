@@ -1,9 +1,13 @@
 use image::{Luma, LumaA, Primitive, Rgb, Rgba};
 
 
+/// Intermediate representation of channel values.  
+/// This is f64 if "f64" feature is enabled, and f32 otherwise.
 #[cfg(not(feature="f64"))]
 pub type Float = f32;
 
+/// Intermediate representation of channel values.  
+/// This is f64 if "f64" feature is enabled, and f32 otherwise.
 #[cfg(feature="f64")]
 pub type Float = f64;
 
@@ -13,7 +17,9 @@ pub trait AsRgba {
     /// Without it, this is fully black.
     const EMPTY: Self;
 
-    /// 0.0 <= value <= 1.0
+    /// 0.0 <= value <= 1.0  
+    /// 
+    /// Integer channels are rounded to nearest and clamped to the range of the type.
     fn from_rgba(rgba: [Float; 4]) -> Self;
 
     /// 0.0 <= value <= 1.0
@@ -56,11 +62,10 @@ macro_rules! impl_as_rgba {
 
             #[inline(always)]
             fn from_rgba(rgba: [Float; 4]) -> Self {
-                const MAX: Float = <$T>::DEFAULT_MAX_VALUE as Float;
                 Rgb([
-                    (rgba[0] * MAX) as $T,
-                    (rgba[1] * MAX) as $T,
-                    (rgba[2] * MAX) as $T,
+                    <$T>::from_float(rgba[0]),
+                    <$T>::from_float(rgba[1]),
+                    <$T>::from_float(rgba[2]),
                 ])
             }
         } 
@@ -96,12 +101,11 @@ macro_rules! impl_as_rgba {
         
             #[inline(always)]
             fn from_rgba(rgba: [Float; 4]) -> Self {
-                const MAX: Float = <$T>::DEFAULT_MAX_VALUE as Float;
                 Rgba([
-                    (rgba[0] * MAX) as $T,
-                    (rgba[1] * MAX) as $T,
-                    (rgba[2] * MAX) as $T,
-                    (rgba[3] * MAX) as $T,
+                    <$T>::from_float(rgba[0]),
+                    <$T>::from_float(rgba[1]),
+                    <$T>::from_float(rgba[2]),
+                    <$T>::from_float(rgba[3]),
                 ])
             }
         } 
@@ -135,9 +139,8 @@ macro_rules! impl_as_rgba {
         
             #[inline(always)]
             fn from_rgba(rgba: [Float; 4]) -> Self {
-                const MAX: Float = <$T>::DEFAULT_MAX_VALUE as Float;
                 let luma = rgb_to_luma(rgba[0], rgba[1], rgba[2]);
-                Luma([(luma * MAX) as $T])
+                Luma([<$T>::from_float(luma)])
             }
         } 
 
@@ -171,17 +174,63 @@ macro_rules! impl_as_rgba {
 
             #[inline(always)]
             fn from_rgba(rgba: [Float; 4]) -> Self {
-                const MAX: Float = <$T>::DEFAULT_MAX_VALUE as Float;
                 let luma = rgb_to_luma(rgba[0], rgba[1], rgba[2]);
                 LumaA([
-                    (luma * MAX) as $T,
-                    (rgba[3] * MAX) as $T
+                    <$T>::from_float(luma),
+                    <$T>::from_float(rgba[3]),
                 ])
             }
         } 
     };
 }
 
+/// Conversion from the intermediate representation to a channel value.
+trait FromFloat {
+    fn from_float(value: Float) -> Self;
+}
+
+macro_rules! impl_from_float_int {
+    ($T: ty) => {
+        impl FromFloat for $T {
+
+            #[inline(always)]
+            fn from_float(value: Float) -> Self {
+                const MIN: Float = <$T>::DEFAULT_MIN_VALUE as Float;
+                const MAX: Float = <$T>::DEFAULT_MAX_VALUE as Float;
+                (value * MAX).round().clamp(MIN, MAX) as $T
+            }
+        }
+    };
+}
+
+macro_rules! impl_from_float_float {
+    ($T: ty) => {
+        impl FromFloat for $T {
+
+            /// Floating point channels are neither rounded nor clamped.
+            #[inline(always)]
+            fn from_float(value: Float) -> Self {
+                value as $T
+            }
+        }
+    };
+}
+
+impl_from_float_int!(u8);
+impl_from_float_int!(u16);
+impl_from_float_int!(u32);
+impl_from_float_int!(u64);
+impl_from_float_int!(usize);
+
+impl_from_float_int!(i8);
+impl_from_float_int!(i16);
+impl_from_float_int!(i32);
+impl_from_float_int!(i64);
+impl_from_float_int!(isize);
+
+impl_from_float_float!(f32);
+impl_from_float_float!(f64);
+
 impl_as_rgba!(u8);
 impl_as_rgba!(u16);
 impl_as_rgba!(u32);
@@ -199,6 +248,6 @@ impl_as_rgba!(f64);
 
 
 #[inline(always)]
-fn rgb_to_luma(r: Float, g: Float, b: Float) -> Float {
+pub(crate) fn rgb_to_luma(r: Float, g: Float, b: Float) -> Float {
     r * 0.2126 + g * 0.7152 + b * 0.0722
 }
\ No newline at end of file
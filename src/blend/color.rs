@@ -51,7 +51,7 @@ pub fn lighter_color(cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (F
 
 
 #[inline(always)]
-fn lum(rgb: (Float, Float, Float)) -> Float {
+pub(super) fn lum(rgb: (Float, Float, Float)) -> Float {
     (0.3 * rgb.0) + (0.59 * rgb.1) + (0.11 * rgb.2)
 }
 
@@ -95,7 +95,7 @@ fn set_lum(rgb: (Float, Float, Float), l: Float) -> (Float, Float, Float) {
 }
 
 #[inline(always)]
-fn sat(rgb: (Float, Float, Float)) -> Float {
+pub(super) fn sat(rgb: (Float, Float, Float)) -> Float {
     max(rgb) - min(rgb)
 }
 
@@ -141,7 +141,7 @@ macro_rules! min_mid_max {
 }
 
 #[inline(always)]
-fn set_sat(mut rgb: (Float, Float, Float), s: Float) -> (Float, Float, Float) {
+pub(super) fn set_sat(mut rgb: (Float, Float, Float), s: Float) -> (Float, Float, Float) {
     min_mid_max!(rgb, (min, mid, max) => {
         if max > min {
             *mid = ((*mid - *min) / (*max - *min)) * s;
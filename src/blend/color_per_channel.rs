@@ -3,7 +3,7 @@
 // https://dunnbypaul.net/blends/
 // https://www.w3.org/TR/compositing-1/
 
-use crate::as_rgba::Float;
+use crate::{as_rgba::Float, blend::hdr};
 
 
 #[inline(always)]
@@ -199,4 +199,68 @@ pub fn divide(base: Float, blend: Float) -> Float {
     else {
         (base / blend).min(1.)
     }
-}
\ No newline at end of file
+}
+
+/// Unlike [`linear_dodge`], the result is not clamped.
+#[inline(always)]
+pub fn add(base: Float, blend: Float) -> Float {
+    hdr::linear_dodge(base, blend)
+}
+
+#[inline(always)]
+pub fn average(base: Float, blend: Float) -> Float {
+    (base + blend) / 2.
+}
+
+#[inline(always)]
+pub fn negation(base: Float, blend: Float) -> Float {
+    1. - (1. - base - blend).abs()
+}
+
+#[inline(always)]
+pub fn reflect(base: Float, blend: Float) -> Float {
+    if blend == 1. {
+        1.
+    }
+    else {
+        (base * base / (1. - blend)).min(1.)
+    }
+}
+
+#[inline(always)]
+pub fn glow(base: Float, blend: Float) -> Float {
+    reflect(blend, base) // inverted reflect
+}
+
+#[inline(always)]
+pub fn freeze(base: Float, blend: Float) -> Float {
+    if base == 1. {
+        1.
+    }
+    else if blend == 0. {
+        0.
+    }
+    else {
+        (1. - (1. - base) * (1. - base) / blend).max(0.)
+    }
+}
+
+#[inline(always)]
+pub fn heat(base: Float, blend: Float) -> Float {
+    freeze(blend, base) // inverted freeze
+}
+
+#[inline(always)]
+pub fn phoenix(base: Float, blend: Float) -> Float {
+    base.min(blend) - base.max(blend) + 1.
+}
+
+#[inline(always)]
+pub fn grain_extract(base: Float, blend: Float) -> Float {
+    (base - blend + 0.5).clamp(0., 1.)
+}
+
+#[inline(always)]
+pub fn grain_merge(base: Float, blend: Float) -> Float {
+    (base + blend - 0.5).clamp(0., 1.)
+}
@@ -1,39 +1,178 @@
 #[cfg(feature = "blend_dissolve")]
 use crate::rng::Rng;
-use crate::{as_rgba::{Float, AsRgba}, blend::{color, color_per_channel}};
+use crate::{as_rgba::{Float, AsRgba}, blend::{color, color_per_channel, hdr, lch, srgb}, AlphaMode, BlendIf, BlendMode, Blender, ChannelMask, CompositeOperator, OverlayOptions};
 
 
+/// Per-call parameters applied on top of the blend mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BlendParams {
+
+    /// Scales the alpha of the top pixel. (0.0 <= value <= 1.0)
+    pub opacity: Float,
+
+    pub operator: CompositeOperator,
+
+    /// Blends in linear light instead of sRGB.
+    pub linear: bool,
+
+    /// Reads and writes premultiplied colors.
+    pub premultiplied: bool,
+
+    /// Uses the unclamped variants of the blend modes.
+    pub hdr: bool,
+
+    /// Scales the opacity of each pixel.
+    pub blend_if: Option<BlendIf>,
+
+    /// Channels of the bottom pixel to write to.
+    pub channel_mask: ChannelMask,
+}
+
+impl BlendParams {
+
+    pub const DEFAULT: Self = Self {
+        opacity: 1.,
+        operator: CompositeOperator::SourceOver,
+        linear: false,
+        premultiplied: false,
+        hdr: false,
+        blend_if: None,
+        channel_mask: ChannelMask::ALL,
+    };
+
+    pub fn from_options(options: &OverlayOptions) -> Self {
+        Self {
+            opacity: options.opacity.clamp(0., 1.),
+            operator: options.composite_operator,
+            linear: options.blend_in_linear,
+            premultiplied: options.alpha_mode == AlphaMode::Premultiplied,
+            hdr: options.hdr,
+            blend_if: options.blend_if,
+            channel_mask: options.channel_mask,
+        }
+    }
+
+    /// Returns `false` if only the source-over of straight sRGB colors is needed,
+    /// which is handled by the blend functions without the `GENERAL` path.
+    pub fn is_general(&self) -> bool {
+        self.operator != CompositeOperator::SourceOver
+            || self.linear
+            || self.premultiplied
+            || self.blend_if.is_some()
+            || !self.channel_mask.is_all()
+    }
+}
+
+
+/// Blends straight or premultiplied RGBA colors.  
+/// Returns `None` if the bottom is left as it is.
+pub(crate) type RgbaBlendFn = fn([Float; 4], [Float; 4], &BlendParams) -> Option<[Float; 4]>;
+
+/// Blend function of a [`BlendMode`].
+#[derive(Clone, Copy)]
+pub(crate) enum ModeBlender {
+    Rgba(RgbaBlendFn),
+
+    /// Dissolve needs a random number generator, which is created for each row.
+    #[cfg(feature = "blend_dissolve")]
+    Dissolve(DissolveBlendFn),
+}
+
+/// Same as [`RgbaBlendFn`] with the random number generator of the row.
+#[cfg(feature = "blend_dissolve")]
+pub(crate) type DissolveBlendFn = fn([Float; 4], [Float; 4], &mut crate::rng::FastUnsecurePrng, &BlendParams) -> Option<[Float; 4]>;
+
+
+/// Shadows `$params` with a copy whose opacity is scaled by the Blend If coverage of the pixels.  
+/// Does nothing unless `$general` is true.
+macro_rules! apply_blend_if {
+    ($general: expr, $params: ident, $bg: expr, $fg: expr) => {
+        let blend_if_params;
+        let $params: &BlendParams = match &$params.blend_if {
+            Some(blend_if) if $general => {
+                let coverage = match $params.premultiplied {
+                    true => blend_if.coverage(unpremultiply($bg), unpremultiply($fg)),
+                    false => blend_if.coverage(($bg[0], $bg[1], $bg[2]), ($fg[0], $fg[1], $fg[2])),
+                };
+                blend_if_params = BlendParams { opacity: $params.opacity * coverage, ..*$params };
+                &blend_if_params
+            }
+            _ => $params,
+        };
+    };
+}
+
+/// Writes back the channels of `$out` allowed by the channel mask.  
+/// Does nothing unless `$general` is true.
+macro_rules! apply_channel_mask {
+    ($general: expr, $params: expr, $out: expr, $bg: expr) => {
+        match $general {
+            true => $out.map(|out| $params.channel_mask.select(out, $bg)),
+            false => $out,
+        }
+    };
+}
+
 #[inline]
 pub fn blend_normal<P1: AsRgba, P2: AsRgba>(
     bg: &mut P1,
     fg: &P2,
 ) {
-    if fg.is_fully_transparent() {
-        return;
-    }
+    write_blended(bg, fg, &BlendParams::DEFAULT, blend_normal_rgba::<false>)
+}
 
-    if bg.is_fully_transparent() || fg.is_fully_opacity() {
-        *bg = P1::from_rgba(fg.to_rgba());
-        return;
-    }
+/// Only the source-over of straight sRGB colors is handled unless `GENERAL` is true.
+pub(crate) fn blend_normal_rgba<const GENERAL: bool>(
+    bg: [Float; 4],
+    fg: [Float; 4],
+    params: &BlendParams,
+) -> Option<[Float; 4]> {
+    let out = 'blend: {
+        apply_blend_if!(GENERAL, params, bg, fg);
+        if params.opacity <= 0. {
+            break 'blend None;
+        }
+        if GENERAL && params.premultiplied {
+            let bg_c = decode(params, bg);
+            let fg_c = decode(params, fg);
 
-    let [bg_r, bg_g, bg_b, bg_a] = bg.to_rgba();
-    let [fg_r, fg_g, fg_b, fg_a] = fg.to_rgba();
+            // as * ab * Cs = ab * cs
+            let blended = (fg_c[0] * bg_c[3], fg_c[1] * bg_c[3], fg_c[2] * bg_c[3]);
+            break 'blend Some(encode(params, composite_premultiplied(bg_c, fg_c, blended, params)));
+        }
+        if GENERAL && params.operator != CompositeOperator::SourceOver {
+            let fg_c = decode(params, fg);
+            let out = composite(decode(params, bg), fg_c, (fg_c[0], fg_c[1], fg_c[2]), params);
+            break 'blend Some(encode(params, out));
+        }
+        if fg[3] == 0. {
+            break 'blend None;
+        }
 
-    let alpha_final = fg_a + bg_a * (1. - fg_a);
-    if alpha_final == 0.0 {
-        *bg = P1::EMPTY;
-        return;
-    };
+        let is_opaque_layer = params.opacity >= 1.;
+        if is_opaque_layer && (bg[3] == 0. || fg[3] == 1.) {
+            break 'blend Some(fg);
+        }
 
-    let tmp = 1. - fg_a;
+        let [bg_r, bg_g, bg_b, bg_a] = decode_if::<GENERAL>(params, bg);
+        let [fg_r, fg_g, fg_b, fg_a] = decode_if::<GENERAL>(params, fg);
+        let fg_a = fg_a * params.opacity;
 
-    *bg = P1::from_rgba([
-        ((fg_r * fg_a) + (bg_r * bg_a) * tmp) / alpha_final,
-        ((fg_g * fg_a) + (bg_g * bg_a) * tmp) / alpha_final,
-        ((fg_b * fg_a) + (bg_b * bg_a) * tmp) / alpha_final,
-        alpha_final
-    ])
+        let alpha_final = fg_a + bg_a * (1. - fg_a);
+        if alpha_final == 0.0 {
+            break 'blend Some([0.; 4]);
+        };
+
+        let tmp = 1. - fg_a;
+
+        Some(encode_if::<GENERAL>(params, [
+            ((fg_r * fg_a) + (bg_r * bg_a) * tmp) / alpha_final,
+            ((fg_g * fg_a) + (bg_g * bg_a) * tmp) / alpha_final,
+            ((fg_b * fg_a) + (bg_b * bg_a) * tmp) / alpha_final,
+            alpha_final
+        ]))
+    };
+    apply_channel_mask!(GENERAL, params, out, bg)
 }
 
 #[cfg(feature = "blend_dissolve")]
@@ -43,65 +182,171 @@ pub fn blend_dissolve<P1: AsRgba, P2: AsRgba>(
     fg: &P2,
     rng: &mut impl Rng,
 ) {
-    if fg.is_fully_transparent() {
-        return;
-    }
-
-    if fg.is_fully_opacity() {
-        *bg = P1::from_rgba(fg.to_rgba());
-        return;
-    }
-    
-    let fg_c = fg.to_rgba();
-    if rng.next() <= fg_c[3] {
-        *bg = P1::from_rgba([
-            fg_c[0],
-            fg_c[1],
-            fg_c[2],
-            1.0,
-        ]);
-        return;
-    }
+    write_blended(bg, fg, &BlendParams::DEFAULT, |bg, fg, params| blend_dissolve_rgba::<false>(bg, fg, rng, params))
 }
 
-macro_rules! blend {
-    ($bg: expr, $fg: expr, ($bg_rgb: pat, $fg_rgb: pat) => $rgb_blend: expr) => {{
+/// Only the source-over of straight sRGB colors is handled unless `GENERAL` is true.
+#[cfg(feature = "blend_dissolve")]
+pub(crate) fn blend_dissolve_rgba<const GENERAL: bool>(
+    bg: [Float; 4],
+    fg: [Float; 4],
+    rng: &mut impl Rng,
+    params: &BlendParams,
+) -> Option<[Float; 4]> {
+    let out = 'blend: {
+        apply_blend_if!(GENERAL, params, bg, fg);
+        if params.opacity <= 0. {
+            break 'blend None;
+        }
+        if GENERAL && params.premultiplied {
+            let [fg_r, fg_g, fg_b, fg_a] = fg;
+            let fg_c = match fg_a > 0. && rng.next() <= fg_a * params.opacity {
+                true => [fg_r / fg_a, fg_g / fg_a, fg_b / fg_a, 1.],
+                false => [0.; 4],
+            };
 
-        if $fg.is_fully_transparent() {
-            return;
+            // The opacity has already been consumed by the dissolve.
+            let params = &BlendParams { opacity: 1., ..*params };
+            let bg_c = decode(params, bg);
+            let fg_c = decode(params, fg_c);
+            let blended = (fg_c[0] * bg_c[3], fg_c[1] * bg_c[3], fg_c[2] * bg_c[3]);
+            break 'blend Some(encode(params, composite_premultiplied(bg_c, fg_c, blended, params)));
         }
-        if $bg.is_fully_transparent() {
-            *$bg = P1::from_rgba($fg.to_rgba());
-            return;
+        if GENERAL && params.operator != CompositeOperator::SourceOver {
+            let [fg_r, fg_g, fg_b, fg_a] = decode(params, fg);
+            let fg_a = if rng.next() <= fg_a * params.opacity { 1. } else { 0. };
+
+            // The opacity has already been consumed by the dissolve.
+            let params = &BlendParams { opacity: 1., ..*params };
+            let out = composite(decode(params, bg), [fg_r, fg_g, fg_b, fg_a], (fg_r, fg_g, fg_b), params);
+            break 'blend Some(encode(params, out));
+        }
+        if fg[3] == 0. {
+            break 'blend None;
         }
-        
-        let [bg_r, bg_g, bg_b, bg_a] = $bg.to_rgba();
-        let [fg_r, fg_g, fg_b, fg_a] = $fg.to_rgba();
-        
-        let alpha_final = fg_a + bg_a * (1. - fg_a);
-        if alpha_final == 0.0 {
-            *$bg = P1::EMPTY;
-            return;
-        };
 
-        let $bg_rgb = (bg_r, bg_g, bg_b);
-        let $fg_rgb = (fg_r, fg_g, fg_b);
-        let (out_r, out_g, out_b) = $rgb_blend;
-        let (out_r, out_g, out_b) = (
-            mix(fg_r, fg_a, bg_r, bg_a, out_r),
-            mix(fg_g, fg_a, bg_g, bg_a, out_g),
-            mix(fg_b, fg_a, bg_b, bg_a, out_b),
-        );
+        if params.opacity >= 1. && fg[3] == 1. {
+            break 'blend Some(fg);
+        }
         
-        *$bg = P1::from_rgba([
-            out_r / alpha_final,
-            out_g / alpha_final,
-            out_b / alpha_final,
-            alpha_final
-        ])
+        match rng.next() <= fg[3] * params.opacity {
+            true => Some([fg[0], fg[1], fg[2], 1.0]),
+            false => None,
+        }
+    };
+    apply_channel_mask!(GENERAL, params, out, bg)
+}
+
+/// Blends straight or premultiplied RGBA colors.  
+/// Evaluates to `None` if the bottom is left as it is.  
+/// 
+/// Only the source-over of straight sRGB colors is handled unless `$general` is true.
+macro_rules! blend {
+    ($general: expr, $bg: expr, $fg: expr, $params: expr, ($bg_rgb: pat, $fg_rgb: pat) => $rgb_blend: expr) => {{
+        let (bg, fg): ([Float; 4], [Float; 4]) = ($bg, $fg);
+        let params: &BlendParams = $params;
+
+        let out = 'blend: {
+            apply_blend_if!($general, params, bg, fg);
+            if params.opacity <= 0. {
+                break 'blend None;
+            }
+            if $general && params.premultiplied {
+                let bg_c = decode(params, bg);
+                let fg_c = decode(params, fg);
+                let $bg_rgb = unpremultiply(bg_c);
+                let $fg_rgb = unpremultiply(fg_c);
+                let (out_r, out_g, out_b) = $rgb_blend;
+                let k = fg_c[3] * bg_c[3];
+                let blended = (out_r * k, out_g * k, out_b * k);
+                break 'blend Some(encode(params, composite_premultiplied(bg_c, fg_c, blended, params)));
+            }
+            if $general && params.operator != CompositeOperator::SourceOver {
+                let bg_c = decode(params, bg);
+                let fg_c = decode(params, fg);
+                let $bg_rgb = (bg_c[0], bg_c[1], bg_c[2]);
+                let $fg_rgb = (fg_c[0], fg_c[1], fg_c[2]);
+                break 'blend Some(encode(params, composite(bg_c, fg_c, $rgb_blend, params)));
+            }
+            if fg[3] == 0. {
+                break 'blend None;
+            }
+            if bg[3] == 0. {
+                let [fg_r, fg_g, fg_b, fg_a] = fg;
+                break 'blend Some([fg_r, fg_g, fg_b, fg_a * params.opacity]);
+            }
+            
+            let [bg_r, bg_g, bg_b, bg_a] = decode_if::<{ $general }>(params, bg);
+            let [fg_r, fg_g, fg_b, fg_a] = decode_if::<{ $general }>(params, fg);
+            let fg_a = fg_a * params.opacity;
+            
+            let alpha_final = fg_a + bg_a * (1. - fg_a);
+            if alpha_final == 0.0 {
+                break 'blend Some([0.; 4]);
+            };
+
+            let $bg_rgb = (bg_r, bg_g, bg_b);
+            let $fg_rgb = (fg_r, fg_g, fg_b);
+            let (out_r, out_g, out_b) = $rgb_blend;
+            let (out_r, out_g, out_b) = (
+                mix(fg_r, fg_a, bg_r, bg_a, out_r),
+                mix(fg_g, fg_a, bg_g, bg_a, out_g),
+                mix(fg_b, fg_a, bg_b, bg_a, out_b),
+            );
+            
+            Some(encode_if::<{ $general }>(params, [
+                out_r / alpha_final,
+                out_g / alpha_final,
+                out_b / alpha_final,
+                alpha_final
+            ]))
+        };
+        apply_channel_mask!($general, params, out, bg)
     }}
 }
 
+/// Blends the pixels as RGBA and writes back the result.  
+/// 
+/// `blend_rgba` does not depend on the pixel types,
+/// so that each blend mode is compiled once instead of once for every pair of pixel types.
+#[inline(always)]
+pub(crate) fn write_blended<P1: AsRgba, P2: AsRgba>(
+    bg: &mut P1,
+    fg: &P2,
+    params: &BlendParams,
+    blend_rgba: impl FnOnce([Float; 4], [Float; 4], &BlendParams) -> Option<[Float; 4]>,
+) {
+    // The operator is only checked for transparent pixels.
+    if fg.is_fully_transparent() && !params.premultiplied && params.operator.keeps_bottom_under_transparent() {
+        return;
+    }
+
+    if let Some(out) = blend_rgba(bg.to_rgba(), fg.to_rgba(), params) {
+        *bg = P1::from_rgba(out);
+    }
+}
+
+/// Blends with a user-defined [`Blender`].
+#[inline]
+pub fn blend_custom<P1: AsRgba, P2: AsRgba, B: Blender>(
+    bg: &mut P1,
+    fg: &P2,
+    blender: &B,
+) {
+    write_blended(bg, fg, &BlendParams::DEFAULT, |bg, fg, params| blend_custom_rgba::<false, B>(bg, fg, blender, params))
+}
+
+/// Only the source-over of straight sRGB colors is handled unless `GENERAL` is true.
+#[inline]
+pub(crate) fn blend_custom_rgba<const GENERAL: bool, B: Blender>(
+    bg: [Float; 4],
+    fg: [Float; 4],
+    blender: &B,
+    params: &BlendParams,
+) -> Option<[Float; 4]> {
+    blend!(GENERAL, bg, fg, params, (bg_rgb, fg_rgb) => blender.blend(bg_rgb, fg_rgb))
+}
+
 #[inline(always)]
 fn mix(fg_c: Float, fg_a: Float, bg_c: Float, bg_a: Float, blended_c: Float) -> Float {
     let blend = (1. - bg_a) * fg_c + bg_a * blended_c;
@@ -110,55 +355,315 @@ fn mix(fg_c: Float, fg_a: Float, bg_c: Float, bg_a: Float, blended_c: Float) ->
     cs + cb * (1. - fg_a)
 }
 
+/// Composites with a Porter-Duff operator other than source-over.
+/// 
+/// `blended` is the result of the blend mode, B(Cb, Cs).  
+/// The opacity interpolates between the bottom and the composited result.
+#[inline(always)]
+fn composite(
+    bg: [Float; 4], 
+    fg: [Float; 4], 
+    blended: (Float, Float, Float), 
+    params: &BlendParams,
+) -> [Float; 4] {
+    let k = fg[3] * bg[3];
+    let blended = (blended.0 * k, blended.1 * k, blended.2 * k);
+    let [out_r, out_g, out_b, out_a] = composite_premultiplied(premultiply(bg), premultiply(fg), blended, params);
+    if out_a <= 0. {
+        return [0.; 4];
+    }
+
+    [
+        out_r / out_a,
+        out_g / out_a,
+        out_b / out_a,
+        out_a,
+    ]
+}
+
+/// Composites premultiplied colors with a Porter-Duff operator.
+/// 
+/// `blended` is the blend mode term already weighted by both alphas, as * ab * B(Cb, Cs).  
+/// The opacity interpolates between the bottom and the composited result.
+#[inline(always)]
+fn composite_premultiplied(
+    bg: [Float; 4], 
+    fg: [Float; 4], 
+    blended: (Float, Float, Float), 
+    params: &BlendParams,
+) -> [Float; 4] {
+    let [bg_r, bg_g, bg_b, bg_a] = bg;
+    let [fg_r, fg_g, fg_b, fg_a] = fg;
+    let (fa, fb) = params.operator.factors(fg_a, bg_a);
+
+    // co = Fa * cs' + Fb * cb
+    // cs' = (1 - ab) * cs + as * ab * B(Cb, Cs)
+    let channel = |fg_c: Float, bg_c: Float, blended_c: Float| {
+        ((1. - bg_a) * fg_c + blended_c) * fa + bg_c * fb
+    };
+    let mut out_a = fg_a * fa + bg_a * fb;
+    let mut out = [
+        channel(fg_r, bg_r, blended.0),
+        channel(fg_g, bg_g, blended.1),
+        channel(fg_b, bg_b, blended.2),
+    ];
+
+    if params.operator == CompositeOperator::Lighter {
+        out_a = out_a.min(1.);
+        out = out.map(|c| c.min(out_a));
+    }
+
+    let t = params.opacity;
+    [
+        bg_r + (out[0] - bg_r) * t,
+        bg_g + (out[1] - bg_g) * t,
+        bg_b + (out[2] - bg_b) * t,
+        bg_a + (out_a - bg_a) * t,
+    ]
+}
+
+#[inline(always)]
+fn premultiply([r, g, b, a]: [Float; 4]) -> [Float; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+/// Returns the straight color, or black for a fully transparent pixel.
+#[inline(always)]
+fn unpremultiply([r, g, b, a]: [Float; 4]) -> (Float, Float, Float) {
+    // Opaque colors are the same in both forms.
+    if a == 1. {
+        return (r, g, b);
+    }
+    if a <= 0. {
+        return (0., 0., 0.);
+    }
+    (r / a, g / a, b / a)
+}
+
+/// Converts the color from sRGB to the space where blending happens.
+#[inline(always)]
+fn decode(params: &BlendParams, rgba: [Float; 4]) -> [Float; 4] {
+    if params.linear {
+        convert(params, rgba, srgb::to_linear)
+    }
+    else {
+        rgba
+    }
+}
+
+/// Converts the color from the space where blending happens to sRGB.
+#[inline(always)]
+fn encode(params: &BlendParams, rgba: [Float; 4]) -> [Float; 4] {
+    if params.linear {
+        convert(params, rgba, srgb::to_srgb)
+    }
+    else {
+        rgba
+    }
+}
+
+/// Same as [`decode`] if `GENERAL` is true, which is the only case where the options can enable linear light.
+#[inline(always)]
+fn decode_if<const GENERAL: bool>(params: &BlendParams, rgba: [Float; 4]) -> [Float; 4] {
+    if GENERAL { decode(params, rgba) } else { rgba }
+}
+
+/// Same as [`encode`] if `GENERAL` is true, which is the only case where the options can enable linear light.
+#[inline(always)]
+fn encode_if<const GENERAL: bool>(params: &BlendParams, rgba: [Float; 4]) -> [Float; 4] {
+    if GENERAL { encode(params, rgba) } else { rgba }
+}
+
+/// Applies a transfer function to the straight color.
+#[inline(always)]
+fn convert(params: &BlendParams, rgba: [Float; 4], f: fn(Float) -> Float) -> [Float; 4] {
+    let [r, g, b, a] = rgba;
+    if !params.premultiplied || a == 1. {
+        return [f(r), f(g), f(b), a];
+    }
+    if a <= 0. {
+        return rgba;
+    }
+    [f(r / a) * a, f(g / a) * a, f(b / a) * a, a]
+}
+
+/// Without `$hdr_blender`, the blender assumes values in [0.0, 1.0] and its inputs are clamped for HDR.
 macro_rules! fn_blend_color_per_channel {
-    ($fn_name: ident, $blender: expr) => {
+    ($fn_name: ident, $fn_name_rgba: ident, $blender: expr) => {
+        fn_blend_color_per_channel!($fn_name, $fn_name_rgba, $blender, |base: Float, blend: Float| {
+            $blender(base.clamp(0., 1.), blend.clamp(0., 1.))
+        });
+    };
+    ($fn_name: ident, $fn_name_rgba: ident, $blender: expr, $hdr_blender: expr) => {
         #[inline]
         pub fn $fn_name<P1: AsRgba, P2: AsRgba>(bg: &mut P1, fg: &P2) {
-            blend!(bg, fg, (bg_rgb, fg_rgb) => (
-                $blender(bg_rgb.0, fg_rgb.0),
-                $blender(bg_rgb.1, fg_rgb.1),
-                $blender(bg_rgb.2, fg_rgb.2),
+            write_blended(bg, fg, &BlendParams::DEFAULT, $fn_name_rgba::<false, false>)
+        }
+
+        /// Uses the HDR variant if `HDR` is true.
+        pub(crate) fn $fn_name_rgba<const GENERAL: bool, const HDR: bool>(bg: [Float; 4], fg: [Float; 4], params: &BlendParams) -> Option<[Float; 4]> {
+            let blender = |base, blend| if HDR { $hdr_blender(base, blend) } else { $blender(base, blend) };
+            blend!(GENERAL, bg, fg, params, (bg_rgb, fg_rgb) => (
+                blender(bg_rgb.0, fg_rgb.0),
+                blender(bg_rgb.1, fg_rgb.1),
+                blender(bg_rgb.2, fg_rgb.2),
             ))
         }
     }
 }
 
+/// Without `$hdr_blender`, the blender assumes values in [0.0, 1.0] and its inputs are clamped for HDR.
 macro_rules! fn_blend_color {
-    ($fn_name: ident, $blender: expr) => {
+    ($fn_name: ident, $fn_name_rgba: ident, $blender: expr) => {
+        fn_blend_color!($fn_name, $fn_name_rgba, $blender, |base: (Float, Float, Float), blend: (Float, Float, Float)| {
+            let clamp = |(r, g, b): (Float, Float, Float)| (r.clamp(0., 1.), g.clamp(0., 1.), b.clamp(0., 1.));
+            $blender(clamp(base), clamp(blend))
+        });
+    };
+    ($fn_name: ident, $fn_name_rgba: ident, $blender: expr, $hdr_blender: expr) => {
+        
+        #[inline]
+        pub fn $fn_name<P1: AsRgba, P2: AsRgba>(bg: &mut P1, fg: &P2) {
+            write_blended(bg, fg, &BlendParams::DEFAULT, $fn_name_rgba::<false, false>)
+        }
+
+        /// Uses the HDR variant if `HDR` is true.
+        pub(crate) fn $fn_name_rgba<const GENERAL: bool, const HDR: bool>(bg: [Float; 4], fg: [Float; 4], params: &BlendParams) -> Option<[Float; 4]> {
+            blend!(GENERAL, bg, fg, params, (bg_rgb, fg_rgb) => match HDR {
+                true => $hdr_blender(bg_rgb, fg_rgb),
+                false => $blender(bg_rgb, fg_rgb),
+            })
+        }
+    }
+}
+
+macro_rules! fn_blend_lch {
+    ($fn_name: ident, $fn_name_rgba: ident, $blender: expr) => {
         
         #[inline]
         pub fn $fn_name<P1: AsRgba, P2: AsRgba>(bg: &mut P1, fg: &P2) {
-            blend!(bg, fg, (bg_rgb, fg_rgb) => $blender(bg_rgb, fg_rgb))
+            write_blended(bg, fg, &BlendParams::DEFAULT, $fn_name_rgba::<false, false>)
+        }
+
+        /// Uses the HDR variant if `HDR` is true.
+        pub(crate) fn $fn_name_rgba<const GENERAL: bool, const HDR: bool>(bg: [Float; 4], fg: [Float; 4], params: &BlendParams) -> Option<[Float; 4]> {
+            blend!(GENERAL, bg, fg, params, (bg_rgb, fg_rgb) => lch::blend_in_lab(bg_rgb, fg_rgb, GENERAL && params.linear, HDR, $blender))
         }
     }
 }
 
 use color_per_channel::*;
 use color::*;
+use lch::*;
+
+fn_blend_color_per_channel!(blend_darken, blend_darken_rgba, darken, darken);
+fn_blend_color_per_channel!(blend_multiply, blend_multiply_rgba, multiply, multiply);
+fn_blend_color_per_channel!(blend_color_burn, blend_color_burn_rgba, color_burn);
+fn_blend_color_per_channel!(blend_linear_burn, blend_linear_burn_rgba, linear_burn);
+fn_blend_color_per_channel!(blend_lighten, blend_lighten_rgba, lighten, lighten);
+fn_blend_color_per_channel!(blend_screen, blend_screen_rgba, screen);
+fn_blend_color_per_channel!(blend_color_dodge, blend_color_dodge_rgba, color_dodge);
+fn_blend_color_per_channel!(blend_linear_dodge, blend_linear_dodge_rgba, linear_dodge, hdr::linear_dodge);
+fn_blend_color_per_channel!(blend_overlay, blend_overlay_rgba, overlay);
+fn_blend_color_per_channel!(blend_soft_light, blend_soft_light_rgba, soft_light);
+fn_blend_color_per_channel!(blend_hard_light, blend_hard_light_rgba, hard_light);
+fn_blend_color_per_channel!(blend_vivid_light, blend_vivid_light_rgba, vivid_light);
+fn_blend_color_per_channel!(blend_linear_light, blend_linear_light_rgba, linear_light);
+fn_blend_color_per_channel!(blend_pin_light, blend_pin_light_rgba, pin_light);
+fn_blend_color_per_channel!(blend_hard_mix, blend_hard_mix_rgba, hard_mix);
+fn_blend_color_per_channel!(blend_difference, blend_difference_rgba, difference, difference);
+fn_blend_color_per_channel!(blend_exclusion, blend_exclusion_rgba, exclusion);
+fn_blend_color_per_channel!(blend_subtract, blend_subtract_rgba, subtract, subtract);
+fn_blend_color_per_channel!(blend_divide, blend_divide_rgba, divide, hdr::divide);
+fn_blend_color_per_channel!(blend_add, blend_add_rgba, add, add);
+fn_blend_color_per_channel!(blend_average, blend_average_rgba, average, average);
+fn_blend_color_per_channel!(blend_negation, blend_negation_rgba, negation);
+fn_blend_color_per_channel!(blend_reflect, blend_reflect_rgba, reflect);
+fn_blend_color_per_channel!(blend_glow, blend_glow_rgba, glow);
+fn_blend_color_per_channel!(blend_freeze, blend_freeze_rgba, freeze);
+fn_blend_color_per_channel!(blend_heat, blend_heat_rgba, heat);
+fn_blend_color_per_channel!(blend_phoenix, blend_phoenix_rgba, phoenix);
+fn_blend_color_per_channel!(blend_grain_extract, blend_grain_extract_rgba, grain_extract, hdr::grain_extract);
+fn_blend_color_per_channel!(blend_grain_merge, blend_grain_merge_rgba, grain_merge, hdr::grain_merge);
+
+fn_blend_color!(blend_hue, blend_hue_rgba, hue, hdr::hue);
+fn_blend_color!(blend_saturation, blend_saturation_rgba, saturation, hdr::saturation);
+fn_blend_color!(blend_color, blend_color_rgba, color, hdr::color);
+fn_blend_color!(blend_luminosity, blend_luminosity_rgba, luminosity, hdr::luminosity);
+fn_blend_color!(blend_darker_color, blend_darker_color_rgba, darker_color, darker_color);
+fn_blend_color!(blend_lighter_color, blend_lighter_color_rgba, lighter_color, lighter_color);
+
+fn_blend_lch!(blend_lch_hue, blend_lch_hue_rgba, lch_hue);
+fn_blend_lch!(blend_lch_chroma, blend_lch_chroma_rgba, lch_chroma);
+fn_blend_lch!(blend_lch_color, blend_lch_color_rgba, lch_color);
+fn_blend_lch!(blend_lch_lightness, blend_lch_lightness_rgba, lch_lightness);
 
-fn_blend_color_per_channel!(blend_darken, darken);
-fn_blend_color_per_channel!(blend_multiply, multiply);
-fn_blend_color_per_channel!(blend_color_burn, color_burn);
-fn_blend_color_per_channel!(blend_linear_burn, linear_burn);
-fn_blend_color_per_channel!(blend_lighten, lighten);
-fn_blend_color_per_channel!(blend_screen, screen);
-fn_blend_color_per_channel!(blend_color_dodge, color_dodge);
-fn_blend_color_per_channel!(blend_linear_dodge, linear_dodge);
-fn_blend_color_per_channel!(blend_overlay, overlay);
-fn_blend_color_per_channel!(blend_soft_light, soft_light);
-fn_blend_color_per_channel!(blend_hard_light, hard_light);
-fn_blend_color_per_channel!(blend_vivid_light, vivid_light);
-fn_blend_color_per_channel!(blend_linear_light, linear_light);
-fn_blend_color_per_channel!(blend_pin_light, pin_light);
-fn_blend_color_per_channel!(blend_hard_mix, hard_mix);
-fn_blend_color_per_channel!(blend_difference, difference);
-fn_blend_color_per_channel!(blend_exclusion, exclusion);
-fn_blend_color_per_channel!(blend_subtract, subtract);
-fn_blend_color_per_channel!(blend_divide, divide);
-
-fn_blend_color!(blend_hue, hue);
-fn_blend_color!(blend_saturation, saturation);
-fn_blend_color!(blend_color, color);
-fn_blend_color!(blend_luminosity, luminosity);
-fn_blend_color!(blend_darker_color, darker_color);
-fn_blend_color!(blend_lighter_color, lighter_color);
\ No newline at end of file
+/// Resolves the blend function of a blend mode for the options.  
+/// 
+/// This is called once per overlay, outside the loops over pixels,
+/// so those loops are compiled once for each pair of pixel types instead of once for each blend mode as well,
+/// and the options which are the same for every pixel are not tested for each pixel.
+pub(crate) fn mode_blender(blend_mode: BlendMode, params: &BlendParams) -> ModeBlender {
+    macro_rules! specialize {
+        ($fn_name_rgba: ident) => {
+            match (params.is_general(), params.hdr) {
+                (false, false) => $fn_name_rgba::<false, false>,
+                (false, true) => $fn_name_rgba::<false, true>,
+                (true, false) => $fn_name_rgba::<true, false>,
+                (true, true) => $fn_name_rgba::<true, true>,
+            }
+        };
+    }
+
+    let blend_rgba: RgbaBlendFn = match blend_mode {
+        #[cfg(feature = "blend_dissolve")]
+        BlendMode::Dissolve => return ModeBlender::Dissolve(match params.is_general() {
+            false => blend_dissolve_rgba::<false>,
+            true => blend_dissolve_rgba::<true>,
+        }),
+        BlendMode::Normal => match params.is_general() {
+            false => blend_normal_rgba::<false>,
+            true => blend_normal_rgba::<true>,
+        },
+        BlendMode::Darken => specialize!(blend_darken_rgba),
+        BlendMode::Multiply => specialize!(blend_multiply_rgba),
+        BlendMode::ColorBurn => specialize!(blend_color_burn_rgba),
+        BlendMode::LinearBurn => specialize!(blend_linear_burn_rgba),
+        BlendMode::Lighten => specialize!(blend_lighten_rgba),
+        BlendMode::Screen => specialize!(blend_screen_rgba),
+        BlendMode::ColorDodge => specialize!(blend_color_dodge_rgba),
+        BlendMode::LinearDodge => specialize!(blend_linear_dodge_rgba),
+        BlendMode::Overlay => specialize!(blend_overlay_rgba),
+        BlendMode::SoftLight => specialize!(blend_soft_light_rgba),
+        BlendMode::HardLight => specialize!(blend_hard_light_rgba),
+        BlendMode::VividLight => specialize!(blend_vivid_light_rgba),
+        BlendMode::LinearLight => specialize!(blend_linear_light_rgba),
+        BlendMode::PinLight => specialize!(blend_pin_light_rgba),
+        BlendMode::HardMix => specialize!(blend_hard_mix_rgba),
+        BlendMode::Difference => specialize!(blend_difference_rgba),
+        BlendMode::Exclusion => specialize!(blend_exclusion_rgba),
+        BlendMode::Subtract => specialize!(blend_subtract_rgba),
+        BlendMode::Divide => specialize!(blend_divide_rgba),
+        BlendMode::DarkerColor => specialize!(blend_darker_color_rgba),
+        BlendMode::LighterColor => specialize!(blend_lighter_color_rgba),
+        BlendMode::Hue => specialize!(blend_hue_rgba),
+        BlendMode::Saturation => specialize!(blend_saturation_rgba),
+        BlendMode::Color => specialize!(blend_color_rgba),
+        BlendMode::Luminosity => specialize!(blend_luminosity_rgba),
+        BlendMode::Add => specialize!(blend_add_rgba),
+        BlendMode::Average => specialize!(blend_average_rgba),
+        BlendMode::Negation => specialize!(blend_negation_rgba),
+        BlendMode::Reflect => specialize!(blend_reflect_rgba),
+        BlendMode::Glow => specialize!(blend_glow_rgba),
+        BlendMode::Freeze => specialize!(blend_freeze_rgba),
+        BlendMode::Heat => specialize!(blend_heat_rgba),
+        BlendMode::Phoenix => specialize!(blend_phoenix_rgba),
+        BlendMode::GrainExtract => specialize!(blend_grain_extract_rgba),
+        BlendMode::GrainMerge => specialize!(blend_grain_merge_rgba),
+        BlendMode::LchHue => specialize!(blend_lch_hue_rgba),
+        BlendMode::LchChroma => specialize!(blend_lch_chroma_rgba),
+        BlendMode::LchColor => specialize!(blend_lch_color_rgba),
+        BlendMode::LchLightness => specialize!(blend_lch_lightness_rgba),
+    };
+    ModeBlender::Rgba(blend_rgba)
+}
@@ -0,0 +1,68 @@
+// Unclamped variants of the blend modes used when `OverlayOptions::hdr` is enabled.
+// Each function drops the upper clamp of its counterpart and keeps the lower one.
+
+use crate::{as_rgba::Float, blend::color::{lum, sat, set_sat}};
+
+
+#[inline(always)]
+pub fn linear_dodge(base: Float, blend: Float) -> Float {
+    base + blend
+}
+
+#[inline(always)]
+pub fn divide(base: Float, blend: Float) -> Float {
+    if blend == 0. {
+        1.
+    }
+    else {
+        base / blend
+    }
+}
+
+#[inline(always)]
+pub fn grain_extract(base: Float, blend: Float) -> Float {
+    (base - blend + 0.5).max(0.)
+}
+
+#[inline(always)]
+pub fn grain_merge(base: Float, blend: Float) -> Float {
+    (base + blend - 0.5).max(0.)
+}
+
+#[inline(always)]
+pub fn hue(cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+    set_lum(set_sat(cs, sat(cb)), lum(cb))
+}
+
+#[inline(always)]
+pub fn saturation(cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+    set_lum(set_sat(cb, sat(cs)), lum(cb))
+}
+
+#[inline(always)]
+pub fn color(cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+    set_lum(cs, lum(cb))
+}
+
+#[inline(always)]
+pub fn luminosity(cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+    set_lum(cb, lum(cs))
+}
+
+
+/// `SetLum` which only pulls negative components back into range.
+#[inline(always)]
+fn set_lum(rgb: (Float, Float, Float), l: Float) -> (Float, Float, Float) {
+    let d = l - lum(rgb);
+    let rgb = (rgb.0 + d, rgb.1 + d, rgb.2 + d);
+
+    let min = Float::min(Float::min(rgb.0, rgb.1), rgb.2);
+    if min < 0. {
+        return (
+            l + (rgb.0 - l) * l / (l - min),
+            l + (rgb.1 - l) * l / (l - min),
+            l + (rgb.2 - l) * l / (l - min),
+        );
+    }
+    rgb
+}
@@ -0,0 +1,130 @@
+// References
+// https://gitlab.gnome.org/GNOME/gimp/-/blob/gimp-2-10/app/operations/layer-modes/gimpoperationlayermode-blend.c
+// https://gitlab.gnome.org/GNOME/babl/-/blob/master/extensions/CIE.c
+// http://www.brucelindbloom.com/index.html?Eqn_RGB_XYZ_Matrix.html
+
+use crate::{as_rgba::Float, blend::srgb};
+
+
+/// CIE L*a*b* color. (L, a, b)
+pub type Lab = (Float, Float, Float);
+
+/// Chroma below this is treated as gray, whose hue is undefined.  
+/// Rounding errors leave grays with a tiny chroma far below anything visible.
+const ACHROMATIC: Float = 1e-3;
+
+#[inline(always)]
+pub fn lch_hue(cb: Lab, cs: Lab) -> Lab {
+    let c_b = cb.1.hypot(cb.2);
+    let c_s = cs.1.hypot(cs.2);
+    if c_b < ACHROMATIC || c_s < ACHROMATIC {
+        return cb;
+    }
+    // Chroma of the bottom, hue of the top.
+    let h = cs.2.atan2(cs.1);
+    (cb.0, c_b * h.cos(), c_b * h.sin())
+}
+
+#[inline(always)]
+pub fn lch_chroma(cb: Lab, cs: Lab) -> Lab {
+    let c_b = cb.1.hypot(cb.2);
+    if c_b < ACHROMATIC {
+        return cb;
+    }
+    // Hue of the bottom, chroma of the top.
+    let k = cs.1.hypot(cs.2) / c_b;
+    (cb.0, cb.1 * k, cb.2 * k)
+}
+
+#[inline(always)]
+pub fn lch_color(cb: Lab, cs: Lab) -> Lab {
+    (cb.0, cs.1, cs.2)
+}
+
+#[inline(always)]
+pub fn lch_lightness(cb: Lab, cs: Lab) -> Lab {
+    (cs.0, cb.1, cb.2)
+}
+
+/// Blends two colors in CIE L*a*b* and returns the result in the original space.
+/// 
+/// `linear` tells whether the colors are already in linear light.  
+/// Otherwise they are decoded from sRGB first and the result is re-encoded.  
+/// The result is clamped to the sRGB gamut, or only to non-negative values if `hdr` is set.
+#[inline(always)]
+pub fn blend_in_lab(
+    cb: (Float, Float, Float),
+    cs: (Float, Float, Float),
+    linear: bool,
+    hdr: bool,
+    f: fn(Lab, Lab) -> Lab,
+) -> (Float, Float, Float) {
+    let decode = |(r, g, b): (Float, Float, Float)| match linear {
+        true => (r, g, b),
+        false => (srgb::to_linear(r), srgb::to_linear(g), srgb::to_linear(b)),
+    };
+    let (r, g, b) = lab_to_rgb(f(rgb_to_lab(decode(cb)), rgb_to_lab(decode(cs))));
+    let max = if hdr { Float::INFINITY } else { 1. };
+    let (r, g, b) = (r.clamp(0., max), g.clamp(0., max), b.clamp(0., max));
+    match linear {
+        true => (r, g, b),
+        false => (srgb::to_srgb(r), srgb::to_srgb(g), srgb::to_srgb(b)),
+    }
+}
+
+
+// sRGB primaries adapted to D50 with the Bradford transform, as babl does.
+// The white point is the sum of each row, so that grays have no chroma.
+const D50: (Float, Float, Float) = (0.964_22, 1., 0.825_21);
+
+const EPSILON: Float = 216. / 24389.;
+const KAPPA: Float = 24389. / 27.;
+
+#[inline(always)]
+fn rgb_to_lab((r, g, b): (Float, Float, Float)) -> Lab {
+    let x = 0.436_074_7 * r + 0.385_064_9 * g + 0.143_080_4 * b;
+    let y = 0.222_504_5 * r + 0.716_878_6 * g + 0.060_616_9 * b;
+    let z = 0.013_932_2 * r + 0.097_104_5 * g + 0.714_173_3 * b;
+
+    let fx = lab_f(x / D50.0);
+    let fy = lab_f(y / D50.1);
+    let fz = lab_f(z / D50.2);
+    (116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz))
+}
+
+#[inline(always)]
+fn lab_to_rgb((l, a, b): Lab) -> (Float, Float, Float) {
+    let fy = (l + 16.) / 116.;
+    let fx = fy + a / 500.;
+    let fz = fy - b / 200.;
+
+    let x = lab_f_inv(fx) * D50.0;
+    let y = lab_f_inv(fy) * D50.1;
+    let z = lab_f_inv(fz) * D50.2;
+    (
+        3.133_856 * x - 1.616_866_7 * y - 0.490_614_6 * z,
+        -0.978_768_4 * x + 1.916_141_5 * y + 0.033_454_0 * z,
+        0.071_945_3 * x - 0.228_991_4 * y + 1.405_242_7 * z,
+    )
+}
+
+#[inline(always)]
+fn lab_f(t: Float) -> Float {
+    if t > EPSILON {
+        t.cbrt()
+    }
+    else {
+        (KAPPA * t + 16.) / 116.
+    }
+}
+
+#[inline(always)]
+fn lab_f_inv(t: Float) -> Float {
+    let t3 = t * t * t;
+    if t3 > EPSILON {
+        t3
+    }
+    else {
+        (116. * t - 16.) / KAPPA
+    }
+}
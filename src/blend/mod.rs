@@ -1,5 +1,8 @@
 mod fns;
 mod color;
 mod color_per_channel;
+mod lch;
+mod hdr;
+mod srgb;
 
-pub use fns::*;
\ No newline at end of file
+pub use fns::*;
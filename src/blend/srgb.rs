@@ -0,0 +1,25 @@
+// References
+// https://www.w3.org/Graphics/Color/srgb
+
+use crate::as_rgba::Float;
+
+
+#[inline(always)]
+pub fn to_linear(c: Float) -> Float {
+    if c <= 0.04045 {
+        c / 12.92
+    }
+    else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline(always)]
+pub fn to_srgb(c: Float) -> Float {
+    if c <= 0.0031308 {
+        c * 12.92
+    }
+    else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
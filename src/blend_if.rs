@@ -0,0 +1,68 @@
+use crate::{as_rgba::Float, BlendIfChannel, BlendIfRange};
+
+
+/// Shows a layer only where the top or the bottom pixel falls in a range, like "Blend If" of Photoshop.  
+/// 
+/// The coverage of both ranges scales the opacity of each pixel, for every blend mode.  
+/// Values are read from the straight colors before any linear-light decoding.
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{BlendIf, BlendIfChannel, BlendIfRange, BlendMode, OverlayOptions};
+/// 
+/// // Let the highlights of the bottom image show through.
+/// let blend_if = BlendIf::new(BlendIfChannel::Gray)
+///     .with_underlying_layer(BlendIfRange::FULL.with_white_split(0.7, 0.9));
+/// 
+/// let options = OverlayOptions::new(BlendMode::Normal).with_blend_if(blend_if);
+/// ``````
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendIf {
+    pub channel: BlendIfChannel,
+
+    /// Range for the top pixel.
+    pub this_layer: BlendIfRange,
+
+    /// Range for the bottom pixel.
+    pub underlying_layer: BlendIfRange,
+}
+
+impl BlendIf {
+
+    pub const fn new(channel: BlendIfChannel) -> Self {
+        Self {
+            channel,
+            this_layer: BlendIfRange::FULL,
+            underlying_layer: BlendIfRange::FULL,
+        }
+    }
+
+    pub const fn with_this_layer(mut self, range: BlendIfRange) -> Self {
+        self.this_layer = range;
+        self
+    }
+
+    pub const fn with_underlying_layer(mut self, range: BlendIfRange) -> Self {
+        self.underlying_layer = range;
+        self
+    }
+
+    /// Takes straight colors. (0.0 <= value <= 1.0)
+    #[inline(always)]
+    pub(crate) fn coverage(&self, bg: (Float, Float, Float), fg: (Float, Float, Float)) -> Float {
+        self.this_layer.coverage(self.value(fg)) * self.underlying_layer.coverage(self.value(bg))
+    }
+
+    #[inline(always)]
+    fn value(&self, (r, g, b): (Float, Float, Float)) -> Float {
+        match self.channel {
+            BlendIfChannel::Gray => 0.3 * r + 0.59 * g + 0.11 * b,
+            BlendIfChannel::Red => r,
+            BlendIfChannel::Green => g,
+            BlendIfChannel::Blue => b,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// Which value of a pixel [`BlendIf`](crate::BlendIf) compares with its ranges.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendIfChannel {
+    /// Luminance of the color, weighted as in the non-separable blend modes.
+    #[default]
+    Gray,
+    Red,
+    Green,
+    Blue,
+}
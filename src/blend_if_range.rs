@@ -0,0 +1,88 @@
+use crate::as_rgba::Float;
+
+
+/// Range of values where a layer shows, with split sliders for a soft transition.  
+/// 
+/// Values are compared in [0.0, 1.0] after clamping.  
+/// Coverage rises from 0.0 to 1.0 between `black[0]` and `black[1]`,
+/// and falls from 1.0 to 0.0 between `white[0]` and `white[1]`.
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::BlendIfRange;
+/// 
+/// // Hide the layer in the shadows, fading it in between 0.2 and 0.4.
+/// let range = BlendIfRange::FULL.with_black_split(0.2, 0.4);
+/// ``````
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlendIfRange {
+    /// Lower and upper end of the black slider.
+    pub black: [Float; 2],
+
+    /// Lower and upper end of the white slider.
+    pub white: [Float; 2],
+}
+
+impl BlendIfRange {
+
+    /// Shows every value.
+    pub const FULL: Self = Self::new(0., 1.);
+
+    /// Hard range without a transition. Values in `black..=white` show.
+    pub const fn new(black: Float, white: Float) -> Self {
+        Self {
+            black: [black, black],
+            white: [white, white],
+        }
+    }
+
+    pub const fn with_black_split(mut self, low: Float, high: Float) -> Self {
+        self.black = [low, high];
+        self
+    }
+
+    pub const fn with_white_split(mut self, low: Float, high: Float) -> Self {
+        self.white = [low, high];
+        self
+    }
+
+    /// 0.0 <= value <= 1.0
+    #[inline(always)]
+    pub(crate) fn coverage(&self, value: Float) -> Float {
+        let value = value.clamp(0., 1.);
+        let [black_low, black_high] = self.black;
+        let [white_low, white_high] = self.white;
+
+        let rise = if value >= black_high {
+            1.
+        }
+        else if value < black_low {
+            0.
+        }
+        else {
+            (value - black_low) / (black_high - black_low)
+        };
+
+        let fall = if value <= white_low {
+            1.
+        }
+        else if value > white_high {
+            0.
+        }
+        else {
+            (white_high - value) / (white_high - white_low)
+        };
+
+        rise * fall
+    }
+}
+
+impl Default for BlendIfRange {
+
+    fn default() -> Self {
+        Self::FULL
+    }
+}
@@ -1,4 +1,5 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
+use crate::{as_rgba::Float, BlendModeCategory};
 
 
 /// Algorithm for blending pixels.  
@@ -7,6 +8,23 @@ use std::fmt;
 /// 
 /// If you need serde::Serialize/Deserialize, please enable "serde" feature.
 /// 
+/// # Parsing
+/// [`FromStr`] accepts the names printed by [`Display`](fmt::Display) and some common aliases.  
+/// Parsing ignores case, spaces, hyphens and underscores.
+/// 
+/// ``````
+/// use image_overlay::BlendMode;
+/// 
+/// assert_eq!("color-dodge".parse(), Ok(BlendMode::ColorDodge));
+/// assert_eq!("Linear Dodge".parse(), Ok(BlendMode::LinearDodge));
+/// assert_eq!("add".parse(), Ok(BlendMode::LinearDodge));
+/// assert_eq!("addition".parse(), Ok(BlendMode::Add));
+/// 
+/// for mode in BlendMode::ALL {
+///     assert_eq!(mode.to_string().parse(), Ok(*mode));
+/// }
+/// ``````
+/// 
 /// # References
 /// [Adobe Photoshop Blending Modes Documentation](https://helpx.adobe.com/en/photoshop/using/blending-modes.html)  
 /// 
@@ -52,6 +70,307 @@ pub enum BlendMode {
     Saturation,
     Color,
     Luminosity,
+
+    /// Like `LinearDodge`, but the sum is not clamped before compositing.  
+    /// Values above 1.0 remain if the bottom image is floating point.  
+    /// 
+    /// Displayed as "Addition", since "add" is parsed as `LinearDodge`.
+    Add,
+    Average,
+    Negation,
+    Reflect,
+    Glow,
+    Freeze,
+    Heat,
+    Phoenix,
+
+    /// GIMP's Grain Extract.
+    GrainExtract,
+
+    /// GIMP's Grain Merge.
+    GrainMerge,
+
+    /// GIMP's LCh Hue, computed in CIE LCh(ab).
+    LchHue,
+
+    /// GIMP's LCh Chroma, computed in CIE LCh(ab).
+    LchChroma,
+
+    /// GIMP's LCh Color, computed in CIE LCh(ab).
+    LchColor,
+
+    /// GIMP's LCh Lightness, computed in CIE LCh(ab).
+    LchLightness,
+}
+
+impl BlendMode {
+
+    /// All blend modes in declaration order.  
+    /// 
+    /// `BlendMode::Dissolve` is included only if "blend_dissolve" feature is enabled.
+    pub const ALL: &'static [BlendMode] = &[
+        BlendMode::Normal,
+        #[cfg(feature = "blend_dissolve")]
+        BlendMode::Dissolve,
+        BlendMode::Darken,
+        BlendMode::Multiply,
+        BlendMode::ColorBurn,
+        BlendMode::LinearBurn,
+        BlendMode::DarkerColor,
+        BlendMode::Lighten,
+        BlendMode::Screen,
+        BlendMode::ColorDodge,
+        BlendMode::LinearDodge,
+        BlendMode::LighterColor,
+        BlendMode::Overlay,
+        BlendMode::SoftLight,
+        BlendMode::HardLight,
+        BlendMode::VividLight,
+        BlendMode::LinearLight,
+        BlendMode::PinLight,
+        BlendMode::HardMix,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+        BlendMode::Subtract,
+        BlendMode::Divide,
+        BlendMode::Hue,
+        BlendMode::Saturation,
+        BlendMode::Color,
+        BlendMode::Luminosity,
+        BlendMode::Add,
+        BlendMode::Average,
+        BlendMode::Negation,
+        BlendMode::Reflect,
+        BlendMode::Glow,
+        BlendMode::Freeze,
+        BlendMode::Heat,
+        BlendMode::Phoenix,
+        BlendMode::GrainExtract,
+        BlendMode::GrainMerge,
+        BlendMode::LchHue,
+        BlendMode::LchChroma,
+        BlendMode::LchColor,
+        BlendMode::LchLightness,
+    ];
+
+    /// Iterator over [`BlendMode::ALL`].
+    pub fn iter() -> impl Iterator<Item = BlendMode> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Group of the mode, as in the blend mode menu of Photoshop.
+    /// 
+    /// ``````
+    /// use image_overlay::{BlendMode, BlendModeCategory};
+    /// 
+    /// assert_eq!(BlendMode::Multiply.category(), BlendModeCategory::Darken);
+    /// assert_eq!(BlendMode::Luminosity.category(), BlendModeCategory::Component);
+    /// ``````
+    pub const fn category(&self) -> BlendModeCategory {
+        match self {
+            #[cfg(feature = "blend_dissolve")]
+            BlendMode::Dissolve => BlendModeCategory::Normal,
+
+            BlendMode::Normal
+            | BlendMode::Average => BlendModeCategory::Normal,
+
+            BlendMode::Darken
+            | BlendMode::Multiply
+            | BlendMode::ColorBurn
+            | BlendMode::LinearBurn
+            | BlendMode::DarkerColor => BlendModeCategory::Darken,
+
+            BlendMode::Lighten
+            | BlendMode::Screen
+            | BlendMode::ColorDodge
+            | BlendMode::LinearDodge
+            | BlendMode::LighterColor
+            | BlendMode::Add => BlendModeCategory::Lighten,
+
+            BlendMode::Overlay
+            | BlendMode::SoftLight
+            | BlendMode::HardLight
+            | BlendMode::VividLight
+            | BlendMode::LinearLight
+            | BlendMode::PinLight
+            | BlendMode::HardMix
+            | BlendMode::Reflect
+            | BlendMode::Glow
+            | BlendMode::Freeze
+            | BlendMode::Heat
+            | BlendMode::GrainMerge => BlendModeCategory::Contrast,
+
+            BlendMode::Difference
+            | BlendMode::Exclusion
+            | BlendMode::Subtract
+            | BlendMode::Divide
+            | BlendMode::Negation
+            | BlendMode::Phoenix
+            | BlendMode::GrainExtract => BlendModeCategory::Inversion,
+
+            BlendMode::Hue
+            | BlendMode::Saturation
+            | BlendMode::Color
+            | BlendMode::Luminosity
+            | BlendMode::LchHue
+            | BlendMode::LchChroma
+            | BlendMode::LchColor
+            | BlendMode::LchLightness => BlendModeCategory::Component,
+        }
+    }
+
+    /// Top color which leaves every opaque bottom color unchanged, as straight RGB.  
+    /// 
+    /// Returns `None` if there is no such color, e.g. for `Normal` where the top replaces the bottom.
+    /// 
+    /// ``````
+    /// use image_overlay::BlendMode;
+    /// 
+    /// assert_eq!(BlendMode::Multiply.neutral_color(), Some([1., 1., 1.]));
+    /// assert_eq!(BlendMode::Screen.neutral_color(), Some([0., 0., 0.]));
+    /// assert_eq!(BlendMode::Overlay.neutral_color(), Some([0.5, 0.5, 0.5]));
+    /// assert_eq!(BlendMode::Normal.neutral_color(), None);
+    /// ``````
+    pub const fn neutral_color(&self) -> Option<[Float; 3]> {
+        const BLACK: Option<[Float; 3]> = Some([0., 0., 0.]);
+        const GRAY: Option<[Float; 3]> = Some([0.5, 0.5, 0.5]);
+        const WHITE: Option<[Float; 3]> = Some([1., 1., 1.]);
+
+        match self {
+            BlendMode::Darken
+            | BlendMode::Multiply
+            | BlendMode::ColorBurn
+            | BlendMode::LinearBurn
+            | BlendMode::DarkerColor
+            | BlendMode::Divide
+            | BlendMode::Phoenix => WHITE,
+
+            BlendMode::Lighten
+            | BlendMode::Screen
+            | BlendMode::ColorDodge
+            | BlendMode::LinearDodge
+            | BlendMode::LighterColor
+            | BlendMode::Difference
+            | BlendMode::Exclusion
+            | BlendMode::Subtract
+            | BlendMode::Add
+            | BlendMode::Negation => BLACK,
+
+            BlendMode::Overlay
+            | BlendMode::SoftLight
+            | BlendMode::HardLight
+            | BlendMode::VividLight
+            | BlendMode::LinearLight
+            | BlendMode::PinLight
+            | BlendMode::GrainExtract
+            | BlendMode::GrainMerge => GRAY,
+
+            // Any gray has no hue to give.
+            BlendMode::LchHue => GRAY,
+
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if each channel of the result depends only on the same channel of both colors.  
+    /// 
+    /// The other modes work on the whole color, like `Hue` or `DarkerColor`.
+    /// 
+    /// ``````
+    /// use image_overlay::BlendMode;
+    /// 
+    /// assert!(BlendMode::Multiply.is_separable());
+    /// assert!(!BlendMode::Color.is_separable());
+    /// ``````
+    pub const fn is_separable(&self) -> bool {
+        !matches!(
+            self,
+            BlendMode::DarkerColor
+            | BlendMode::LighterColor
+            | BlendMode::Hue
+            | BlendMode::Saturation
+            | BlendMode::Color
+            | BlendMode::Luminosity
+            | BlendMode::LchHue
+            | BlendMode::LchChroma
+            | BlendMode::LchColor
+            | BlendMode::LchLightness
+        )
+    }
+
+    /// Returns `true` if swapping the bottom color and the top color gives the same blended color.  
+    /// 
+    /// This is about the blend mode only. Compositing with alpha still depends on the order.
+    /// 
+    /// ``````
+    /// use image_overlay::BlendMode;
+    /// 
+    /// assert!(BlendMode::Screen.is_commutative());
+    /// assert!(!BlendMode::Overlay.is_commutative());
+    /// ``````
+    pub const fn is_commutative(&self) -> bool {
+        matches!(
+            self,
+            BlendMode::Darken
+            | BlendMode::Multiply
+            | BlendMode::LinearBurn
+            | BlendMode::Lighten
+            | BlendMode::Screen
+            | BlendMode::LinearDodge
+            | BlendMode::HardMix
+            | BlendMode::Difference
+            | BlendMode::Exclusion
+            | BlendMode::Add
+            | BlendMode::Average
+            | BlendMode::Negation
+            | BlendMode::Phoenix
+            | BlendMode::GrainMerge
+        )
+    }
+
+    /// Returns `true` if the mode keeps values above 1.0 when [`OverlayOptions::hdr`](crate::OverlayOptions::hdr) is enabled.  
+    /// 
+    /// The other modes assume values in [0.0, 1.0], like the modes Photoshop disables for 32-bit images.
+    /// Their inputs are clamped to that range when HDR is enabled.
+    /// 
+    /// ``````
+    /// use image_overlay::BlendMode;
+    /// 
+    /// assert!(BlendMode::LinearDodge.supports_hdr());
+    /// assert!(!BlendMode::Screen.supports_hdr());
+    /// assert!(!BlendMode::ColorDodge.supports_hdr());
+    /// ``````
+    pub const fn supports_hdr(&self) -> bool {
+        match self {
+            #[cfg(feature = "blend_dissolve")]
+            BlendMode::Dissolve => true,
+
+            BlendMode::Normal
+            | BlendMode::Darken
+            | BlendMode::Multiply
+            | BlendMode::DarkerColor
+            | BlendMode::Lighten
+            | BlendMode::LinearDodge
+            | BlendMode::LighterColor
+            | BlendMode::Difference
+            | BlendMode::Subtract
+            | BlendMode::Divide
+            | BlendMode::Hue
+            | BlendMode::Saturation
+            | BlendMode::Color
+            | BlendMode::Luminosity
+            | BlendMode::Add
+            | BlendMode::Average
+            | BlendMode::GrainExtract
+            | BlendMode::GrainMerge
+            | BlendMode::LchHue
+            | BlendMode::LchChroma
+            | BlendMode::LchColor
+            | BlendMode::LchLightness => true,
+
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for BlendMode {
@@ -88,7 +407,104 @@ impl fmt::Display for BlendMode {
             BlendMode::Saturation => "Saturation",
             BlendMode::Color => "Color",
             BlendMode::Luminosity => "Luminosity",
+            BlendMode::Add => "Addition",
+            BlendMode::Average => "Average",
+            BlendMode::Negation => "Negation",
+            BlendMode::Reflect => "Reflect",
+            BlendMode::Glow => "Glow",
+            BlendMode::Freeze => "Freeze",
+            BlendMode::Heat => "Heat",
+            BlendMode::Phoenix => "Phoenix",
+            BlendMode::GrainExtract => "GrainExtract",
+            BlendMode::GrainMerge => "GrainMerge",
+            BlendMode::LchHue => "LchHue",
+            BlendMode::LchChroma => "LchChroma",
+            BlendMode::LchColor => "LchColor",
+            BlendMode::LchLightness => "LchLightness",
         };
         write!(f, "{}", mode_str)
     }
-}
\ No newline at end of file
+}
+
+impl FromStr for BlendMode {
+    type Err = ParseBlendModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        let mode = match name.as_str() {
+            "normal" => BlendMode::Normal,
+
+            #[cfg(feature = "blend_dissolve")]
+            "dissolve" => BlendMode::Dissolve,
+
+            "darken" | "darkenonly" => BlendMode::Darken,
+            "multiply" => BlendMode::Multiply,
+            "colorburn" | "burn" => BlendMode::ColorBurn,
+            "linearburn" => BlendMode::LinearBurn,
+            "darkercolor" => BlendMode::DarkerColor,
+            "lighten" | "lightenonly" => BlendMode::Lighten,
+            "screen" => BlendMode::Screen,
+            "colordodge" | "dodge" => BlendMode::ColorDodge,
+            "lineardodge" | "add" => BlendMode::LinearDodge,
+            "lightercolor" => BlendMode::LighterColor,
+            "overlay" => BlendMode::Overlay,
+            "softlight" => BlendMode::SoftLight,
+            "hardlight" => BlendMode::HardLight,
+            "vividlight" => BlendMode::VividLight,
+            "linearlight" => BlendMode::LinearLight,
+            "pinlight" => BlendMode::PinLight,
+            "hardmix" => BlendMode::HardMix,
+            "difference" => BlendMode::Difference,
+            "exclusion" => BlendMode::Exclusion,
+            "subtract" => BlendMode::Subtract,
+            "divide" => BlendMode::Divide,
+            "hue" => BlendMode::Hue,
+            "saturation" => BlendMode::Saturation,
+            "color" | "colour" => BlendMode::Color,
+            "luminosity" => BlendMode::Luminosity,
+            "addition" | "addunclamped" => BlendMode::Add,
+            "average" => BlendMode::Average,
+            "negation" => BlendMode::Negation,
+            "reflect" => BlendMode::Reflect,
+            "glow" => BlendMode::Glow,
+            "freeze" => BlendMode::Freeze,
+            "heat" => BlendMode::Heat,
+            "phoenix" => BlendMode::Phoenix,
+            "grainextract" => BlendMode::GrainExtract,
+            "grainmerge" => BlendMode::GrainMerge,
+            "lchhue" => BlendMode::LchHue,
+            "lchchroma" => BlendMode::LchChroma,
+            "lchcolor" => BlendMode::LchColor,
+            "lchlightness" => BlendMode::LchLightness,
+            _ => return Err(ParseBlendModeError(s.to_owned())),
+        };
+        Ok(mode)
+    }
+}
+
+
+/// An error returned when parsing a [`BlendMode`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBlendModeError(String);
+
+impl ParseBlendModeError {
+
+    /// The string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ParseBlendModeError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown blend mode: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBlendModeError {}
@@ -0,0 +1,26 @@
+/// Group of blend modes, as in the blend mode menu of Photoshop.  
+/// 
+/// See [`BlendMode::category`](crate::BlendMode::category).
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendModeCategory {
+    /// Modes which mix the top over the bottom.
+    Normal,
+
+    /// Modes whose result is never lighter than the bottom.
+    Darken,
+
+    /// Modes whose result is never darker than the bottom.
+    Lighten,
+
+    /// Modes which darken or lighten depending on the top.
+    Contrast,
+
+    /// Modes based on the difference or the inverse of the colors.
+    Inversion,
+
+    /// Modes which combine components such as hue, saturation and lightness.
+    Component,
+}
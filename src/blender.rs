@@ -0,0 +1,51 @@
+use crate::as_rgba::Float;
+
+
+/// A user-defined blend mode for [`overlay_with`](crate::overlay_with).  
+/// 
+/// `blend` is B(Cb, Cs) of the [W3C Compositing spec](https://www.w3.org/TR/compositing-1/#blending),
+/// which takes the straight colors of the bottom and the top pixel.  
+/// Alpha, opacity and the other options are applied around it exactly as for the built-in [`BlendMode`](crate::BlendMode)s.
+/// 
+/// Closures which take and return RGB tuples implement this trait.
+/// The channels are [`Float`], which depends on "f64" feature.
+/// For a formula applied to each channel independently, use [`PerChannel`](crate::PerChannel).
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{Blender, Float};
+/// 
+/// // Takes the hue and saturation of the top and the brightest channel of the bottom.
+/// struct KeepValue;
+/// 
+/// impl Blender for KeepValue {
+///     fn blend(&self, cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+///         let value_b = cb.0.max(cb.1).max(cb.2);
+///         let value_s = cs.0.max(cs.1).max(cs.2);
+///         if value_s == 0. {
+///             return (value_b, value_b, value_b);
+///         }
+///         let k = value_b / value_s;
+///         (cs.0 * k, cs.1 * k, cs.2 * k)
+///     }
+/// }
+/// ``````
+pub trait Blender {
+
+    /// Takes the bottom color and the top color as straight RGB. (0.0 <= value <= 1.0 for integer images)  
+    /// 
+    /// The colors of floating point images are passed as they are, without the clamp of the built-in modes,
+    /// so they may be above 1.0, such as for HDR images drawn with [`OverlayOptions::hdr`](crate::OverlayOptions::hdr).
+    /// Clamp them in the blender if it assumes [0.0, 1.0].
+    fn blend(&self, cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float);
+}
+
+impl<F> Blender for F
+where 
+    F: Fn((Float, Float, Float), (Float, Float, Float)) -> (Float, Float, Float),
+{
+    #[inline(always)]
+    fn blend(&self, cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+        self(cb, cs)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::as_rgba::Float;
+
+
+/// Which channels of the bottom image an overlay writes to.  
+/// 
+/// The channels are those of the RGBA representation (see [`AsRgba`](crate::AsRgba)).
+/// Masked channels keep the value of the bottom pixel.
+/// With `AlphaMode::Premultiplied`, the stored premultiplied values are masked as they are.
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{BlendMode, ChannelMask, OverlayOptions};
+/// 
+/// // Multiply only the red channel.
+/// let options = OverlayOptions::new(BlendMode::Multiply).with_channel_mask(ChannelMask::RED);
+/// 
+/// // Leave alpha untouched.
+/// let options = OverlayOptions::new(BlendMode::Normal).with_channel_mask(ChannelMask::RGB);
+/// ``````
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelMask {
+    pub red: bool,
+    pub green: bool,
+    pub blue: bool,
+    pub alpha: bool,
+}
+
+impl ChannelMask {
+
+    pub const ALL: Self = Self::new(true, true, true, true);
+    pub const RGB: Self = Self::new(true, true, true, false);
+    pub const RED: Self = Self::new(true, false, false, false);
+    pub const GREEN: Self = Self::new(false, true, false, false);
+    pub const BLUE: Self = Self::new(false, false, true, false);
+    pub const ALPHA: Self = Self::new(false, false, false, true);
+
+    pub const fn new(red: bool, green: bool, blue: bool, alpha: bool) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Returns `true` if every channel is written.
+    pub const fn is_all(&self) -> bool {
+        self.red && self.green && self.blue && self.alpha
+    }
+
+    /// Takes each channel from `written` if it is enabled, and from `original` otherwise.
+    #[inline(always)]
+    pub(crate) fn select(&self, written: [Float; 4], original: [Float; 4]) -> [Float; 4] {
+        [
+            if self.red { written[0] } else { original[0] },
+            if self.green { written[1] } else { original[1] },
+            if self.blue { written[2] } else { original[2] },
+            if self.alpha { written[3] } else { original[3] },
+        ]
+    }
+}
+
+impl Default for ChannelMask {
+
+    fn default() -> Self {
+        Self::ALL
+    }
+}
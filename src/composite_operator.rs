@@ -0,0 +1,123 @@
+use std::fmt;
+use crate::as_rgba::Float;
+
+
+/// Porter-Duff operator for compositing the top image onto the bottom image.  
+/// 
+/// It can be paired with any [`BlendMode`](crate::BlendMode).
+/// The blend mode decides the color where both images overlap,
+/// and the operator decides how much of each image remains.  
+/// 
+/// Only the area covered by the top image is composited.
+/// Pixels of the bottom image outside that area are left untouched, even for operators such as `SourceIn`.  
+/// 
+/// Opacity interpolates between the bottom pixel and the composited pixel.
+/// For `SourceOver`, this is the same as scaling the alpha of the top pixel.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # References
+/// [Compositing and Blending Level 1](https://www.w3.org/TR/compositing-1/#porterduffcompositingoperators)
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompositeOperator {
+    /// Neither the top nor the bottom remains.
+    Clear,
+
+    /// Only the top remains.
+    Copy,
+
+    /// Only the bottom remains.
+    Destination,
+
+    /// The top is placed over the bottom.
+    #[default]
+    SourceOver,
+
+    /// The bottom is placed over the top.
+    DestinationOver,
+
+    /// The top remains where it overlaps the bottom.
+    SourceIn,
+
+    /// The bottom remains where it overlaps the top.
+    DestinationIn,
+
+    /// The top remains where it does not overlap the bottom.
+    SourceOut,
+
+    /// The bottom remains where it does not overlap the top.
+    DestinationOut,
+
+    /// The top is placed over the bottom where it overlaps the bottom.
+    SourceAtop,
+
+    /// The bottom is placed over the top where it overlaps the top.
+    DestinationAtop,
+
+    /// The non-overlapping regions of the top and the bottom remain.
+    Xor,
+
+    /// The top and the bottom are added.
+    Lighter,
+}
+
+impl CompositeOperator {
+
+    /// Returns `(Fa, Fb)`, the fractions of the top and the bottom that remain.
+    #[inline(always)]
+    pub(crate) fn factors(self, fg_a: Float, bg_a: Float) -> (Float, Float) {
+        match self {
+            CompositeOperator::Clear => (0., 0.),
+            CompositeOperator::Copy => (1., 0.),
+            CompositeOperator::Destination => (0., 1.),
+            CompositeOperator::SourceOver => (1., 1. - fg_a),
+            CompositeOperator::DestinationOver => (1. - bg_a, 1.),
+            CompositeOperator::SourceIn => (bg_a, 0.),
+            CompositeOperator::DestinationIn => (0., fg_a),
+            CompositeOperator::SourceOut => (1. - bg_a, 0.),
+            CompositeOperator::DestinationOut => (0., 1. - fg_a),
+            CompositeOperator::SourceAtop => (bg_a, 1. - fg_a),
+            CompositeOperator::DestinationAtop => (1. - bg_a, fg_a),
+            CompositeOperator::Xor => (1. - bg_a, 1. - fg_a),
+            CompositeOperator::Lighter => (1., 1.),
+        }
+    }
+
+    /// Returns `true` if a fully transparent top pixel leaves the bottom pixel as it is.
+    #[inline(always)]
+    pub(crate) fn keeps_bottom_under_transparent(self) -> bool {
+        // Fb is 1 where the top alpha is 0.
+        matches!(self, 
+            CompositeOperator::Destination
+            | CompositeOperator::SourceOver
+            | CompositeOperator::DestinationOver
+            | CompositeOperator::DestinationOut
+            | CompositeOperator::SourceAtop
+            | CompositeOperator::Xor
+            | CompositeOperator::Lighter
+        )
+    }
+}
+
+impl fmt::Display for CompositeOperator {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator_str = match self {
+            CompositeOperator::Clear => "Clear",
+            CompositeOperator::Copy => "Copy",
+            CompositeOperator::Destination => "Destination",
+            CompositeOperator::SourceOver => "SourceOver",
+            CompositeOperator::DestinationOver => "DestinationOver",
+            CompositeOperator::SourceIn => "SourceIn",
+            CompositeOperator::DestinationIn => "DestinationIn",
+            CompositeOperator::SourceOut => "SourceOut",
+            CompositeOperator::DestinationOut => "DestinationOut",
+            CompositeOperator::SourceAtop => "SourceAtop",
+            CompositeOperator::DestinationAtop => "DestinationAtop",
+            CompositeOperator::Xor => "Xor",
+            CompositeOperator::Lighter => "Lighter",
+        };
+        write!(f, "{}", operator_str)
+    }
+}
@@ -0,0 +1,150 @@
+use image::{Rgba, Rgba32FImage};
+use crate::Layer;
+
+
+/// A stack of layers flattened onto a canvas.  
+/// 
+/// Layers are overlaid in order, so the first layer is the bottom-most one.  
+/// Layers can be nested with [`LayerGroup`](crate::LayerGroup).
+/// 
+/// The canvas is always an `Rgba32FImage` with straight alpha, so the background is an `Rgba<f32>`
+/// even if "f64" feature is enabled.
+/// Layers of other color types are converted while they are overlaid,
+/// and the flattened image can be converted with `DynamicImage::from(image).into_rgba8()` and the like.
+/// 
+/// # Usage
+/// ``````
+/// use image::{DynamicImage, Rgba};
+/// use image_overlay::{BlendMode, Composition, Layer};
+/// 
+/// let photo = DynamicImage::new_rgb8(100, 100);
+/// let logo = DynamicImage::new_rgba8(20, 20);
+/// 
+/// let mut composition = Composition::new(100, 100)
+///     .with_background(Rgba([1., 1., 1., 1.]));
+/// 
+/// composition.push(Layer::borrowed(&photo));
+/// composition.push(Layer::new(logo).with_offset(70, 70).with_blend_mode(BlendMode::Screen).with_opacity(0.8));
+/// composition.toggle(0);
+/// 
+/// let image = composition.flatten();
+/// assert_eq!(image.dimensions(), (100, 100));
+/// ``````
+#[derive(Debug, Clone)]
+pub struct Composition<'a> {
+    width: u32,
+    height: u32,
+    background: Rgba<f32>,
+    layers: Vec<Layer<'a>>,
+}
+
+impl<'a> Composition<'a> {
+
+    /// Creates an empty composition with a transparent background.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            background: Rgba([0.; 4]),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn with_background(mut self, background: Rgba<f32>) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn background(&self) -> Rgba<f32> {
+        self.background
+    }
+
+    pub fn set_background(&mut self, background: Rgba<f32>) {
+        self.background = background;
+    }
+
+    pub fn layers(&self) -> &[Layer<'a>] {
+        &self.layers
+    }
+
+    pub fn layer(&self, index: usize) -> Option<&Layer<'a>> {
+        self.layers.get(index)
+    }
+
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Layer<'a>> {
+        self.layers.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Adds a layer on top of the others.
+    pub fn push(&mut self, layer: Layer<'a>) {
+        self.layers.push(layer);
+    }
+
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, layer: Layer<'a>) {
+        self.layers.insert(index, layer);
+    }
+
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Layer<'a> {
+        self.layers.remove(index)
+    }
+
+    /// Moves the layer at `from` so that it ends up at `to`, shifting the layers in between.
+    /// 
+    /// # Panics
+    /// Panics if `from` or `to` is out of bounds.
+    pub fn move_layer(&mut self, from: usize, to: usize) {
+        let layer = self.layers.remove(from);
+        self.layers.insert(to, layer);
+    }
+
+    /// # Panics
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.layers.swap(a, b);
+    }
+
+    /// Toggles the visibility of the layer and returns the new visibility.
+    /// 
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        let layer = &mut self.layers[index];
+        layer.visible = !layer.visible;
+        layer.visible
+    }
+
+    /// Overlays the visible layers in order onto the background.
+    pub fn flatten(&self) -> Rgba32FImage {
+        let mut canvas = Rgba32FImage::from_pixel(self.width, self.height, self.background);
+        self.flatten_into(&mut canvas);
+        canvas
+    }
+
+    /// Overlays the visible layers in order onto the given canvas, ignoring the background.  
+    /// The canvas must have straight alpha.
+    pub fn flatten_into(&self, canvas: &mut Rgba32FImage) {
+        for layer in &self.layers {
+            layer.draw(canvas, 0, 0);
+        }
+    }
+}
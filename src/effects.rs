@@ -0,0 +1,160 @@
+use image::{GenericImage, GenericImageView};
+use crate::{alpha_mask::{blur_radius, AlphaMask}, as_rgba::Float, overlay_with_options, AlphaMode, AsRgba, BlendMode, ChannelMask, CompositeOperator, OverlayOptions, Shadow, Stroke, StrokePosition};
+
+
+/// The largest stroke width and blur size in pixels.
+const MAX_SIZE: Float = 250.;
+
+
+/// Layer effects drawn together with the top image by [`overlay_with_effects`](crate::overlay_with_effects).  
+/// 
+/// From bottom to top, the drop shadow, the outer glow, an outside stroke, the top image 
+/// and an inside or center stroke are drawn.  
+/// The effects take the alpha mode, `blend_in_linear` and `hdr` from the layer,
+/// and their opacity is multiplied by the opacity of the layer.
+/// They are always drawn with `CompositeOperator::SourceOver` onto every channel without Blend If,
+/// so that the transparent parts of their images do not affect the bottom image.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Effects {
+    pub drop_shadow: Option<Shadow>,
+    pub outer_glow: Option<Shadow>,
+    pub stroke: Option<Stroke>,
+}
+
+impl Effects {
+
+    /// No effects.
+    pub const fn new() -> Self {
+        Self {
+            drop_shadow: None,
+            outer_glow: None,
+            stroke: None,
+        }
+    }
+
+    pub const fn with_drop_shadow(mut self, drop_shadow: Shadow) -> Self {
+        self.drop_shadow = Some(drop_shadow);
+        self
+    }
+
+    pub const fn with_outer_glow(mut self, outer_glow: Shadow) -> Self {
+        self.outer_glow = Some(outer_glow);
+        self
+    }
+
+    pub const fn with_stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    /// Draws the effects beneath the layer at (x, y).
+    pub(crate) fn draw_below<B, F>(&self, bottom: &mut B, top: &F, x: i64, y: i64, options: OverlayOptions) 
+    where 
+        B: GenericImage::<Pixel: AsRgba>,
+        F: GenericImageView::<Pixel: AsRgba>,
+    {
+        for shadow in [self.drop_shadow, self.outer_glow].iter().flatten() {
+            draw_shadow(bottom, top, x, y, shadow, options);
+        }
+        if let Some(stroke) = self.stroke.filter(|stroke| stroke.position == StrokePosition::Outside) {
+            draw_stroke(bottom, top, x, y, &stroke, options);
+        }
+    }
+
+    /// Draws the effects above the layer at (x, y).
+    pub(crate) fn draw_above<B, F>(&self, bottom: &mut B, top: &F, x: i64, y: i64, options: OverlayOptions) 
+    where 
+        B: GenericImage::<Pixel: AsRgba>,
+        F: GenericImageView::<Pixel: AsRgba>,
+    {
+        if let Some(stroke) = self.stroke.filter(|stroke| stroke.position != StrokePosition::Outside) {
+            draw_stroke(bottom, top, x, y, &stroke, options);
+        }
+    }
+}
+
+fn draw_shadow<B, F>(bottom: &mut B, top: &F, x: i64, y: i64, shadow: &Shadow, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    let blur = match shadow.blur.is_nan() {
+        true => 0.,
+        false => shadow.blur.min(MAX_SIZE / 3.),
+    };
+
+    // The spread takes its part of the blur size and dilates the layer by it instead.
+    let spread = shadow.spread.clamp(0., 1.);
+    let (dilation, sigma) = match blur > 0. && spread > 0. {
+        true => (blur * 3. * spread, blur * (1. - spread)),
+        false => (0., blur),
+    };
+
+    // The antialiased edge of the dilation takes one more pixel.
+    let padding = match dilation > 0. {
+        true => (dilation.ceil() as u32).saturating_add(1).saturating_add(blur_radius(sigma)),
+        false => blur_radius(sigma),
+    };
+    let mut mask = AlphaMask::from_image(top, padding);
+    mask.dilate(dilation);
+    mask.blur(sigma);
+
+    let image = mask.to_image(shadow.color, options.alpha_mode == AlphaMode::Premultiplied);
+    let options = effect_options(shadow.blend_mode, shadow.opacity, options);
+
+    let padding = i64::from(padding);
+    let x = x.saturating_add(shadow.offset_x).saturating_sub(padding);
+    let y = y.saturating_add(shadow.offset_y).saturating_sub(padding);
+    overlay_with_options(bottom, &image, x, y, options);
+}
+
+fn draw_stroke<B, F>(bottom: &mut B, top: &F, x: i64, y: i64, stroke: &Stroke, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    if stroke.width.is_nan() || stroke.width <= 0. {
+        return;
+    }
+    let width = stroke.width.min(MAX_SIZE);
+
+    let padding = match stroke.position {
+        StrokePosition::Inside => 0,
+        StrokePosition::Outside => (width.ceil() as u32).saturating_add(1),
+        StrokePosition::Center => ((width / 2.).ceil() as u32).saturating_add(1),
+    };
+    let mut mask = AlphaMask::from_image(top, padding);
+    let distance = mask.signed_distance();
+
+    let coverage = |d: Float| d.clamp(0., 1.);
+    for (value, d) in mask.values.iter_mut().zip(distance) {
+        let alpha = *value;
+        *value = match stroke.position {
+            // The layer is drawn above, so it covers the inner edge.
+            StrokePosition::Outside => coverage(width + 0.5 - d),
+            StrokePosition::Inside => coverage(width + 0.5 + d) * alpha,
+            StrokePosition::Center => coverage(width / 2. + 0.5 - d.abs()),
+        };
+    }
+
+    let image = mask.to_image(stroke.color, options.alpha_mode == AlphaMode::Premultiplied);
+    let options = effect_options(stroke.blend_mode, stroke.opacity, options);
+
+    let padding = i64::from(padding);
+    overlay_with_options(bottom, &image, x.saturating_sub(padding), y.saturating_sub(padding), options);
+}
+
+/// Returns the options to draw an effect with, taking the color handling and the opacity from the layer.
+fn effect_options(blend_mode: BlendMode, opacity: Float, options: OverlayOptions) -> OverlayOptions {
+    OverlayOptions {
+        blend_mode,
+        opacity: opacity.clamp(0., 1.) * options.opacity.clamp(0., 1.),
+        composite_operator: CompositeOperator::SourceOver,
+        blend_if: None,
+        channel_mask: ChannelMask::ALL,
+        ..options
+    }
+}
@@ -0,0 +1,124 @@
+use image::GenericImageView;
+use crate::{as_rgba::Float, AsRgba};
+
+
+/// Filter for sampling an image at fractional coordinates.  
+/// 
+/// Colors are filtered with premultiplied alpha, so transparent pixels do not bleed into their neighbors.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    Nearest,
+
+    #[default]
+    Bilinear,
+
+    /// Catmull-Rom spline.
+    Bicubic,
+}
+
+impl Interpolation {
+
+    /// Samples the image at (x, y), where pixel (i, j) covers [i, i + 1) x [j, j + 1).  
+    /// Coordinates outside the image are clamped to the edge.
+    /// 
+    /// Returns a premultiplied color if `premultiplied`, otherwise a straight color.
+    /// The overshoot of bicubic is clamped, keeping colors above 1 if `hdr`.
+    /// The image must not be empty.
+    pub(crate) fn sample<I>(self, image: &I, x: Float, y: Float, premultiplied: bool, hdr: bool) -> [Float; 4]
+    where 
+        I: GenericImageView::<Pixel: AsRgba>,
+    {
+        let [r, g, b, a] = match self {
+            Interpolation::Nearest => {
+                fetch(image, x.floor() as i64, y.floor() as i64, premultiplied)
+            }
+            Interpolation::Bilinear => {
+                let (x0, tx) = split(x);
+                let (y0, ty) = split(y);
+                filter(image, (x0, y0), &[1. - tx, tx], &[1. - ty, ty], premultiplied)
+            }
+            Interpolation::Bicubic => {
+                let (x0, tx) = split(x);
+                let (y0, ty) = split(y);
+                let [r, g, b, a] = filter(image, (x0 - 1, y0 - 1), &catmull_rom(tx), &catmull_rom(ty), premultiplied);
+
+                // Bicubic may overshoot.
+                let a = a.clamp(0., 1.);
+                let max = if hdr { Float::INFINITY } else { a };
+                [r.clamp(0., max), g.clamp(0., max), b.clamp(0., max), a]
+            }
+        };
+
+        if premultiplied {
+            return [r, g, b, a];
+        }
+        if a <= 0. {
+            return [0.; 4];
+        }
+        [r / a, g / a, b / a, a]
+    }
+}
+
+/// Splits a coordinate into the index of the pixel whose center is on the left and the distance from that center.
+#[inline(always)]
+fn split(x: Float) -> (i64, Float) {
+    let x = x - 0.5;
+    let x0 = x.floor();
+    (x0 as i64, x - x0)
+}
+
+#[inline(always)]
+fn catmull_rom(t: Float) -> [Float; 4] {
+    [
+        ((-0.5 * t + 1.) * t - 0.5) * t,
+        (1.5 * t - 2.5) * t * t + 1.,
+        ((-1.5 * t + 2.) * t + 0.5) * t,
+        (0.5 * t - 0.5) * t * t,
+    ]
+}
+
+/// Weighted sum of the premultiplied pixels starting from `origin`.
+#[inline(always)]
+fn filter<I>(image: &I, (x0, y0): (i64, i64), wx: &[Float], wy: &[Float], premultiplied: bool) -> [Float; 4]
+where 
+    I: GenericImageView::<Pixel: AsRgba>,
+{
+    let mut out = [0.; 4];
+    for (j, wy) in wy.iter().enumerate() {
+        for (i, wx) in wx.iter().enumerate() {
+            let c = fetch(image, x0 + i as i64, y0 + j as i64, premultiplied);
+            let w = wx * wy;
+            for k in 0..4 {
+                out[k] += c[k] * w;
+            }
+        }
+    }
+    out
+}
+
+/// Returns the premultiplied color of the pixel, clamping the coordinate to the edge.
+#[inline(always)]
+fn fetch<I>(image: &I, x: i64, y: i64, premultiplied: bool) -> [Float; 4]
+where 
+    I: GenericImageView::<Pixel: AsRgba>,
+{
+    let (width, height) = image.dimensions();
+    let x = x.clamp(0, i64::from(width) - 1) as u32;
+    let y = y.clamp(0, i64::from(height) - 1) as u32;
+
+    #[cfg(debug_assertions)]
+    let [r, g, b, a] = image.get_pixel(x, y).to_rgba();
+
+    #[cfg(not(debug_assertions))]
+    let [r, g, b, a] = unsafe { image.unsafe_get_pixel(x, y) }.to_rgba();
+
+    if premultiplied {
+        [r, g, b, a]
+    }
+    else {
+        [r * a, g * a, b * a, a]
+    }
+}
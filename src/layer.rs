@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+use image::{DynamicImage, Rgba32FImage};
+use crate::{as_rgba::Float, overlay_dyn_img_to_img_with_options, BlendMode, LayerGroup, OverlayOptions};
+
+
+/// A layer of a [`Composition`](crate::Composition).  
+/// 
+/// The image is either owned or borrowed.
+#[derive(Debug, Clone)]
+pub struct Layer<'a> {
+    pub content: LayerContent<'a>,
+
+    /// Offset of the content on the canvas.  
+    /// For a group, it is added to the offsets of its children.
+    pub x: i64,
+
+    /// Offset of the content on the canvas.  
+    /// For a group, it is added to the offsets of its children.
+    pub y: i64,
+
+    /// Blend mode, opacity and the other options used to overlay the content.  
+    /// 
+    /// See [`GroupBlending`](crate::GroupBlending) for how they apply to a group.
+    pub options: OverlayOptions,
+
+    /// Hidden layers are skipped by [`Composition::flatten`](crate::Composition::flatten).
+    pub visible: bool,
+}
+
+/// What a [`Layer`] draws.
+#[derive(Debug, Clone)]
+pub enum LayerContent<'a> {
+    Image(Cow<'a, DynamicImage>),
+    Group(LayerGroup<'a>),
+}
+
+impl Layer<'static> {
+
+    pub fn new(image: DynamicImage) -> Self {
+        Self::from_content(LayerContent::Image(Cow::Owned(image)))
+    }
+}
+
+impl<'a> Layer<'a> {
+
+    pub fn borrowed(image: &'a DynamicImage) -> Self {
+        Self::from_content(LayerContent::Image(Cow::Borrowed(image)))
+    }
+
+    pub fn group(group: LayerGroup<'a>) -> Self {
+        Self::from_content(LayerContent::Group(group))
+    }
+
+    fn from_content(content: LayerContent<'a>) -> Self {
+        Self {
+            content,
+            x: 0,
+            y: 0,
+            options: OverlayOptions::default(),
+            visible: true,
+        }
+    }
+
+    pub fn with_offset(mut self, x: i64, y: i64) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_options(mut self, options: OverlayOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.options.blend_mode = blend_mode;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: Float) -> Self {
+        self.options.opacity = opacity;
+        self
+    }
+
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Draws the layer onto the canvas, with (x, y) as the origin.
+    pub(crate) fn draw(&self, canvas: &mut Rgba32FImage, x: i64, y: i64) {
+        if !self.visible {
+            return;
+        }
+
+        let (x, y) = (x.saturating_add(self.x), y.saturating_add(self.y));
+        match &self.content {
+            LayerContent::Image(image) => {
+                overlay_dyn_img_to_img_with_options(canvas, image, x, y, self.options);
+            }
+            LayerContent::Group(group) => {
+                group.draw(canvas, x, y, self.options);
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use image::Rgba32FImage;
+use crate::{overlay_buffer_with_options, AlphaMode, Layer, OverlayOptions};
+
+
+/// How a [`LayerGroup`] is blended with the layers below it.
+/// 
+/// # References
+/// [Compositing and Blending Level 1](https://www.w3.org/TR/compositing-1/#isolationandgroups)
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupBlending {
+    /// Each child is blended straight into the backdrop with its own blend mode, like "Pass Through" of Photoshop.  
+    /// 
+    /// Only the opacity of the group is used.
+    /// It interpolates between the backdrop and the result, 
+    /// which matches a non-isolated group with the normal blend mode.  
+    /// The blend mode, the channel mask and the other options of the group are ignored.
+    #[default]
+    PassThrough,
+
+    /// The children are composited onto a transparent backdrop,
+    /// and the result is overlaid as a single image with the options of the group.  
+    /// The result has straight alpha, so the alpha mode of the group is ignored.
+    Isolated,
+}
+
+/// Layers nested in a [`Layer`].
+/// 
+/// # Usage
+/// ``````
+/// use image::DynamicImage;
+/// use image_overlay::{BlendMode, Composition, GroupBlending, Layer, LayerGroup};
+/// 
+/// let mut group = LayerGroup::new(GroupBlending::Isolated);
+/// group.layers.push(Layer::new(DynamicImage::new_rgba8(50, 50)));
+/// group.layers.push(Layer::new(DynamicImage::new_rgba8(50, 50)).with_blend_mode(BlendMode::Multiply));
+/// 
+/// let mut composition = Composition::new(100, 100);
+/// composition.push(Layer::new(DynamicImage::new_rgb8(100, 100)));
+/// composition.push(Layer::group(group).with_offset(25, 25).with_blend_mode(BlendMode::Screen).with_opacity(0.5));
+/// 
+/// let image = composition.flatten();
+/// ``````
+#[derive(Debug, Clone, Default)]
+pub struct LayerGroup<'a> {
+
+    /// The first layer is the bottom-most one.
+    pub layers: Vec<Layer<'a>>,
+
+    pub blending: GroupBlending,
+}
+
+impl<'a> LayerGroup<'a> {
+
+    pub fn new(blending: GroupBlending) -> Self {
+        Self {
+            layers: Vec::new(),
+            blending,
+        }
+    }
+
+    pub fn with_layers(mut self, layers: Vec<Layer<'a>>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Draws the group onto the canvas, with (x, y) as the origin of the children.
+    pub(crate) fn draw(&self, canvas: &mut Rgba32FImage, x: i64, y: i64, options: OverlayOptions) {
+        match self.blending {
+            GroupBlending::Isolated => {
+                let mut group = Rgba32FImage::new(canvas.width(), canvas.height());
+                for layer in &self.layers {
+                    layer.draw(&mut group, x, y);
+                }
+                overlay_buffer_with_options(canvas, &group, 0, 0, options.with_alpha_mode(AlphaMode::Straight));
+            }
+            GroupBlending::PassThrough => {
+                // The opacity may be f64 with "f64" feature.
+                #[allow(clippy::unnecessary_cast)]
+                let opacity = options.opacity.clamp(0., 1.) as f32;
+                if opacity <= 0. {
+                    return;
+                }
+
+                let backdrop = (opacity < 1.).then(|| canvas.clone());
+                for layer in &self.layers {
+                    layer.draw(canvas, x, y);
+                }
+                if let Some(backdrop) = backdrop {
+                    for (out, bg) in canvas.pixels_mut().zip(backdrop.pixels()) {
+                        *out = lerp_premultiplied(bg.0, out.0, opacity).into();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Interpolates in premultiplied space and returns the straight color.
+fn lerp_premultiplied(from: [f32; 4], to: [f32; 4], t: f32) -> [f32; 4] {
+    let a = from[3] + (to[3] - from[3]) * t;
+    if a <= 0. {
+        return [0.; 4];
+    }
+
+    let channel = |i: usize| (from[i] * from[3] + (to[i] * to[3] - from[i] * from[3]) * t) / a;
+    [channel(0), channel(1), channel(2), a]
+}
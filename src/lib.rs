@@ -1,5 +1,5 @@
 //! # image-overlay  
-//! This crate supports overlaying images with 27 blend modes.  
+//! This crate supports overlaying images with 41 blend modes.  
 //! 
 //! # Usage
 //! This is to be used with image crate as following:
@@ -29,16 +29,69 @@
 //! 
 //! If you need overlay with BlendMode::Dissolve, please enable "blend_dissolve" feature.
 //! 
-//! If you need serde::Serialize/Deserialize for BlendMode, please enable "serde" feature.  
+//! If you need parallel overlay for ImageBuffer (e.g. `overlay_par`), please enable "rayon" feature.
+//! 
+//! If you need serde::Serialize/Deserialize for BlendMode, BlendModeCategory, CompositeOperator, AlphaMode, GroupBlending, Affine, Interpolation, Tiling, WrapMode, Anchor, Placement, Effects, Shadow, Stroke, StrokePosition, BlendIf, BlendIfChannel, BlendIfRange, ChannelMask and OverlayOptions, please enable "serde" feature.  
 
+mod affine;
+mod alignment;
+mod alpha_mask;
+mod anchor;
+mod alpha_mode;
+mod blend_if;
+mod blend_if_channel;
+mod blend_if_range;
 mod blend_mode;
+mod blend_mode_category;
+mod blender;
+mod channel_mask;
+mod composite_operator;
+mod composition;
+mod layer;
+mod layer_group;
+mod interpolation;
+mod tiling;
+mod placement;
+mod effects;
+mod shadow;
+mod stroke;
+mod stroke_position;
+mod wrap_mode;
 mod overlay;
+mod overlay_options;
+mod overlay_error;
+mod per_channel;
 mod as_rgba;
+mod simd;
 
 #[cfg(feature = "blend_dissolve")]
 pub mod rng;
 pub mod blend;
 
-pub use as_rgba::AsRgba;
-pub use blend_mode::BlendMode;
+pub use affine::Affine;
+pub use anchor::Anchor;
+pub use alpha_mode::AlphaMode;
+pub use as_rgba::{AsRgba, Float};
+pub use blend_if::BlendIf;
+pub use blend_if_channel::BlendIfChannel;
+pub use blend_if_range::BlendIfRange;
+pub use blend_mode::{BlendMode, ParseBlendModeError};
+pub use blend_mode_category::BlendModeCategory;
+pub use blender::Blender;
+pub use channel_mask::ChannelMask;
+pub use composite_operator::CompositeOperator;
+pub use composition::Composition;
+pub use layer::{Layer, LayerContent};
+pub use layer_group::{GroupBlending, LayerGroup};
+pub use interpolation::Interpolation;
+pub use tiling::Tiling;
+pub use placement::Placement;
+pub use effects::Effects;
+pub use shadow::Shadow;
+pub use stroke::Stroke;
+pub use stroke_position::StrokePosition;
+pub use wrap_mode::WrapMode;
+pub use overlay_options::OverlayOptions;
+pub use overlay_error::OverlayError;
+pub use per_channel::PerChannel;
 pub use overlay::*;
\ No newline at end of file
@@ -1,10 +1,18 @@
 #[cfg(feature = "blend_dissolve")]
 use crate::rng::FastUnsecurePrng;
-use crate::{blend_mode::BlendMode, AsRgba};
-use image::{DynamicImage, GenericImage, GenericImageView};
+use crate::{as_rgba::{rgb_to_luma, Float}, blend::BlendParams, blend_mode::BlendMode, simd, Affine, AlphaMode, AsRgba, Blender, CompositeOperator, Effects, Interpolation, OverlayError, OverlayOptions, Placement, Tiling};
+use image::{DynamicImage, GenericImage, GenericImageView, ImageBuffer, Pixel, Rgba};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::{borrow::Cow, ops::{Deref, DerefMut}};
 
+/// Images of unsupported color types must be converted by [`to_supported`] beforehand,
+/// or handled by `$unsupported`.
 macro_rules! dynamic_map {
-    ($dynimage: expr, $image:pat_param, $action: expr) => {{
+    ($dynimage: expr, $image:pat_param, $action: expr) => {
+        dynamic_map!($dynimage, $image, $action, unreachable!("unsupported color type"))
+    };
+    ($dynimage: expr, $image:pat_param, $action: expr, $unsupported: expr) => {{
         match $dynimage {
             DynamicImage::ImageLuma8($image) => $action,
             DynamicImage::ImageLuma16($image) => $action,
@@ -16,7 +24,40 @@ macro_rules! dynamic_map {
             DynamicImage::ImageRgba8($image) => $action,
             DynamicImage::ImageRgba16($image) => $action,
             DynamicImage::ImageRgba32F($image) => $action,
-             _ => unimplemented!(),
+            _ => $unsupported,
+        }
+    }};
+}
+
+/// Binds `$blender` to a function which creates the blend function for a row of the top image,
+/// then evaluates `$action`.  
+/// 
+/// Each row gets its own blend function so that rows can be processed in any order.  
+/// The blend mode is resolved before `$action`, so `$action` does not depend on it.
+macro_rules! blend_mode_map {
+    ($blend_mode: expr, $params: expr, $top_dimensions: expr, ($P1: ty, $P2: ty), $blender: ident => $action: expr) => {{
+        use crate::blend::{write_blended, ModeBlender};
+
+        match crate::blend::mode_blender($blend_mode, &$params) {
+            #[cfg(feature = "blend_dissolve")]
+            ModeBlender::Dissolve(blend_rgba) => {
+                let (width, height) = $top_dimensions;
+
+                // Ensure the same state if the top image and the row are the same.
+                let $blender = |row: u32| {
+                    let mut rng = FastUnsecurePrng::for_row(width, height, row);
+                    move |bg: &mut $P1, fg: &$P2, params: &BlendParams| {
+                        write_blended(bg, fg, params, |bg, fg, params| blend_rgba(bg, fg, &mut rng, params))
+                    }
+                };
+                $action
+            }
+            ModeBlender::Rgba(blend_rgba) => {
+                let $blender = |_row: u32| move |bg: &mut $P1, fg: &$P2, params: &BlendParams| {
+                    write_blended(bg, fg, params, blend_rgba)
+                };
+                $action
+            }
         }
     }};
 }
@@ -28,6 +69,8 @@ macro_rules! dynamic_map {
 /// Do NOT use this function for DynamicImage. Use [`overlay_dyn_img`] insted.  
 /// Because [GenericImage for DynamicImage looses precision](https://github.com/image-rs/image/issues/1592)  and slower.  
 /// 
+/// If both images are ImageBuffer, [`overlay_buffer`] is faster.  
+/// 
 /// # Usage
 /// ``````
 /// use image::{ImageBuffer, Rgba, Luma};
@@ -47,6 +90,132 @@ where
     B: GenericImage::<Pixel: AsRgba>,
     F: GenericImageView::<Pixel: AsRgba>,
 {
+    overlay_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options.  
+/// 
+/// # Note
+/// Do NOT use this function for DynamicImage. Use [`overlay_dyn_img_with_options`] insted.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_with_options, BlendMode, OverlayOptions};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// 
+/// let options = OverlayOptions::new(BlendMode::Screen).with_opacity(0.5);
+/// overlay_with_options(&mut dest, &src, 0, 0, options);
+/// ``````
+/// 
+/// See [`overlay`] for details.
+pub fn overlay_with_options<B, F>(bottom: &mut B, top: &F, x: i64, y: i64, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_impl(bottom, top, x, y, options, |_, _| 1.)
+}
+
+/// Overlay an image at a given coordinate (x, y) with a user-defined [`Blender`].  
+/// 
+/// The blender is used instead of `options.blend_mode`, and the other options apply as usual.  
+/// It is dispatched statically, so it runs as fast as the built-in blend modes.
+/// 
+/// # Note
+/// Do NOT use this function for DynamicImage. Convert it to an ImageBuffer instead.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_with, Float, OverlayOptions};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// 
+/// // Keeps the brighter color by its sum of channels.
+/// let brighter = |cb: (Float, Float, Float), cs: (Float, Float, Float)| {
+///     if cs.0 + cs.1 + cs.2 > cb.0 + cb.1 + cb.2 { cs } else { cb }
+/// };
+/// overlay_with(&mut dest, &src, 0, 0, &brighter, OverlayOptions::default().with_opacity(0.5));
+/// ``````
+/// 
+/// See [`overlay`] and [`Blender`] for details.
+pub fn overlay_with<B, F, L>(bottom: &mut B, top: &F, x: i64, y: i64, blender: &L, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    L: Blender,
+{
+    use crate::blend::{blend_custom_rgba, write_blended};
+
+    match BlendParams::from_options(&options).is_general() {
+        false => overlay_rows(bottom, top, x, y, options, |_, _| 1., |_row| |bg: &mut B::Pixel, fg: &F::Pixel, params: &BlendParams| {
+            write_blended(bg, fg, params, |bg, fg, params| blend_custom_rgba::<false, L>(bg, fg, blender, params))
+        }),
+        true => overlay_rows(bottom, top, x, y, options, |_, _| 1., |_row| |bg: &mut B::Pixel, fg: &F::Pixel, params: &BlendParams| {
+            write_blended(bg, fg, params, |bg, fg, params| blend_custom_rgba::<true, L>(bg, fg, blender, params))
+        }),
+    }
+}
+
+/// Overlay an image buffer at a given coordinate (x, y) with blend mode.  
+/// 
+/// This walks the rows of both buffers as slices of channel data
+/// instead of getting and putting every pixel through [`GenericImage`], so it is faster than [`overlay`].  
+/// The result is the same as [`overlay`].  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_buffer, BlendMode};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u16>, Vec<u16>>::new(100, 100);
+/// 
+/// overlay_buffer(&mut dest, &src, 0, 0, BlendMode::default());
+/// ``````
+/// 
+/// See [`overlay`] for details.
+pub fn overlay_buffer<P1, C1, P2, C2>(
+    bottom: &mut ImageBuffer<P1, C1>, 
+    top: &ImageBuffer<P2, C2>, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) 
+where 
+    P1: Pixel + AsRgba,
+    C1: DerefMut<Target = [P1::Subpixel]>,
+    P2: Pixel + AsRgba,
+    C2: Deref<Target = [P2::Subpixel]>,
+{
+    overlay_buffer_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image buffer at a given coordinate (x, y) with options.  
+/// 
+/// See [`overlay_buffer`] for details.
+pub fn overlay_buffer_with_options<P1, C1, P2, C2>(
+    bottom: &mut ImageBuffer<P1, C1>, 
+    top: &ImageBuffer<P2, C2>, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) 
+where 
+    P1: Pixel + AsRgba,
+    C1: DerefMut<Target = [P1::Subpixel]>,
+    P2: Pixel + AsRgba,
+    C2: Deref<Target = [P2::Subpixel]>,
+{
+    let params = BlendParams::from_options(&options);
+    if params.opacity <= 0. {
+        return;
+    }
+
     let (
         origin_bottom_x, 
         origin_bottom_y, 
@@ -55,82 +224,426 @@ where
         range_width, 
         range_height
     ) = overlay_bounds_ext(bottom.dimensions(), top.dimensions(), x, y);
-        
-    macro_rules! overlay {
-        (($bg: pat, $fg: pat) => $blend: expr) => {{
-            for y in 0..range_height {
-                for x in 0..range_width {
-                    let (bg_x, bg_y) = (origin_bottom_x + x, origin_bottom_y + y);
-                    let (fg_x, fg_y) = (origin_top_x + x, origin_top_y + y);
-
-                    #[cfg(debug_assertions)] {
-                        let (mut _bg, _fg) = (
-                            bottom.get_pixel(bg_x, bg_y),
-                            top.get_pixel(fg_x, fg_y),
-                        );
-
-                        let ($bg, $fg) = (&mut _bg, &_fg);
-                        $blend;
-
-                        bottom.put_pixel(bg_x, bg_y, _bg);
-                    }
-                    
-                    #[cfg(not(debug_assertions))] unsafe {
-                        let (mut _bg, _fg) = (
-                            bottom.unsafe_get_pixel(bg_x, bg_y),
-                            top.unsafe_get_pixel(fg_x, fg_y),
-                        );
+    if range_width == 0 || range_height == 0 {
+        return;
+    }
 
-                        let ($bg, $fg) = (&mut _bg, &_fg);
-                        $blend;
+    let (bottom_channels, top_channels) = (
+        usize::from(P1::CHANNEL_COUNT), 
+        usize::from(P2::CHANNEL_COUNT),
+    );
+    let (bottom_row_len, top_row_len) = (
+        bottom.width() as usize * bottom_channels, 
+        top.width() as usize * top_channels,
+    );
+    let (bottom_start, bottom_end) = (
+        origin_bottom_x as usize * bottom_channels,
+        (origin_bottom_x + range_width) as usize * bottom_channels,
+    );
+    let (top_start, top_end) = (
+        origin_top_x as usize * top_channels,
+        (origin_top_x + range_width) as usize * top_channels,
+    );
 
-                        bottom.unsafe_put_pixel(bg_x, bg_y, _bg);
-                    }
-                }
+    blend_mode_map!(options.blend_mode, params, top.dimensions(), (P1, P2), row_blender => {
+        let bottom_rows = bottom
+            .chunks_exact_mut(bottom_row_len)
+            .skip(origin_bottom_y as usize)
+            .take(range_height as usize);
+        let top_rows = top
+            .chunks_exact(top_row_len)
+            .skip(origin_top_y as usize);
+
+        for (y, (bg_row, fg_row)) in bottom_rows.zip(top_rows).enumerate() {
+            // Only the blender for dissolve has a state.
+            #[allow(unused_mut)]
+            let mut blend = row_blender(origin_top_y + y as u32);
+
+            let bg_row = bg_row[bottom_start..bottom_end].chunks_exact_mut(bottom_channels);
+            let fg_row = fg_row[top_start..top_end].chunks_exact(top_channels);
+
+            for (bg, fg) in bg_row.zip(fg_row) {
+                blend(P1::from_slice_mut(bg), P2::from_slice(fg), &params);
             }
-        }};
+        }
+    })
+}
+
+/// Overlay an RGBA8 image buffer at a given coordinate (x, y) with blend mode.  
+/// 
+/// This uses vectorized kernels (SSE2/AVX2 on x86_64, detected at runtime) for
+/// `Normal`, `Multiply`, `Screen`, `Overlay`, `Darken`, `Lighten`, `Difference`, `LinearDodge` and `Subtract`.  
+/// Other blend modes and CPUs fall back to [`overlay_buffer`].  
+/// The output is within ±1 of [`overlay_buffer`], so the vectorized kernels are used only if you call this function.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_buffer, overlay_rgba8, BlendMode};
+/// 
+/// let bottom = ImageBuffer::from_fn(64, 64, |x, y| Rgba([x as u8 * 4, y as u8 * 4, 128, 200]));
+/// let top = ImageBuffer::from_fn(64, 64, |x, y| Rgba([y as u8 * 4, 255 - x as u8, 64, x as u8 * 4]));
+/// 
+/// let mut dest = bottom.clone();
+/// overlay_rgba8(&mut dest, &top, 10, -10, BlendMode::Overlay);
+/// 
+/// let mut expected = bottom.clone();
+/// overlay_buffer(&mut expected, &top, 10, -10, BlendMode::Overlay);
+/// 
+/// for (a, b) in dest.iter().zip(expected.iter()) {
+///     assert!(a.abs_diff(*b) <= 1);
+/// }
+/// ``````
+/// 
+/// See [`overlay`] for details.
+pub fn overlay_rgba8<C1, C2>(
+    bottom: &mut ImageBuffer<Rgba<u8>, C1>, 
+    top: &ImageBuffer<Rgba<u8>, C2>, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) 
+where 
+    C1: DerefMut<Target = [u8]>,
+    C2: Deref<Target = [u8]>,
+{
+    overlay_rgba8_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an RGBA8 image buffer at a given coordinate (x, y) with options.  
+/// 
+/// Only `CompositeOperator::SourceOver` with straight alpha and without `blend_in_linear`, `blend_if` or `channel_mask` is vectorized.  
+/// 
+/// See [`overlay_rgba8`] for details.
+pub fn overlay_rgba8_with_options<C1, C2>(
+    bottom: &mut ImageBuffer<Rgba<u8>, C1>, 
+    top: &ImageBuffer<Rgba<u8>, C2>, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) 
+where 
+    C1: DerefMut<Target = [u8]>,
+    C2: Deref<Target = [u8]>,
+{
+    overlay_rgba8_with(bottom, top, x, y, options, simd::rgba8_row_blender)
+}
+
+/// Same as [`overlay_rgba8_with_options`], but never uses AVX2.  
+/// It is only for testing the SSE2 path on CPUs with AVX2, and is not a part of the public API.
+#[doc(hidden)]
+pub fn overlay_rgba8_sse2_with_options<C1, C2>(
+    bottom: &mut ImageBuffer<Rgba<u8>, C1>, 
+    top: &ImageBuffer<Rgba<u8>, C2>, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) 
+where 
+    C1: DerefMut<Target = [u8]>,
+    C2: Deref<Target = [u8]>,
+{
+    overlay_rgba8_with(bottom, top, x, y, options, simd::rgba8_row_blender_sse2)
+}
+
+/// Overlays with the row blender selected by `row_blender`, or falls back to [`overlay_buffer_with_options`].
+fn overlay_rgba8_with<C1, C2>(
+    bottom: &mut ImageBuffer<Rgba<u8>, C1>, 
+    top: &ImageBuffer<Rgba<u8>, C2>, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions,
+    row_blender: fn(BlendMode) -> Option<simd::RowBlender>,
+) 
+where 
+    C1: DerefMut<Target = [u8]>,
+    C2: Deref<Target = [u8]>,
+{
+    let row_blender = match (options.composite_operator, options.blend_in_linear, options.alpha_mode) {
+        (CompositeOperator::SourceOver, false, AlphaMode::Straight) if options.blend_if.is_none() && options.channel_mask.is_all() => row_blender(options.blend_mode),
+        _ => None,
+    };
+    let Some(row_blender) = row_blender else {
+        return overlay_buffer_with_options(bottom, top, x, y, options);
+    };
+
+    let params = BlendParams::from_options(&options);
+    if params.opacity <= 0. {
+        return;
+    }
+
+    let (
+        origin_bottom_x, 
+        origin_bottom_y, 
+        origin_top_x, 
+        origin_top_y, 
+        range_width, 
+        range_height
+    ) = overlay_bounds_ext(bottom.dimensions(), top.dimensions(), x, y);
+    if range_width == 0 || range_height == 0 {
+        return;
+    }
+
+    let (bottom_row_len, top_row_len) = (bottom.width() as usize * 4, top.width() as usize * 4);
+    let (bottom_start, top_start) = (origin_bottom_x as usize * 4, origin_top_x as usize * 4);
+    let len = range_width as usize * 4;
+
+    // Float is f64 if "f64" feature is enabled.
+    #[allow(clippy::unnecessary_cast)]
+    let opacity = params.opacity as f32;
+
+    let bottom_rows = bottom
+        .chunks_exact_mut(bottom_row_len)
+        .skip(origin_bottom_y as usize)
+        .take(range_height as usize);
+    let top_rows = top
+        .chunks_exact(top_row_len)
+        .skip(origin_top_y as usize);
+
+    for (bg_row, fg_row) in bottom_rows.zip(top_rows) {
+        row_blender(
+            &mut bg_row[bottom_start..bottom_start + len], 
+            &fg_row[top_start..top_start + len], 
+            opacity,
+        );
+    }
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with blend mode.  
+/// 
+/// The mask is placed at (mask_x, mask_y) relative to the top image.  
+/// Each mask pixel scales the coverage of the top pixel by its luminance multiplied by its alpha,
+/// so both grayscale masks and alpha masks can be used.  
+/// Parts of the top image outside the mask are not drawn.  
+/// 
+/// # Note
+/// Do NOT use this function for DynamicImage. Use [`overlay_masked_dyn_img`] insted.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba, Luma};
+/// use image_overlay::{overlay_masked, BlendMode};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let mask = ImageBuffer::<Luma<u8>, Vec<u8>>::new(50, 50);
+/// 
+/// overlay_masked(&mut dest, &src, 0, 0, &mask, 25, 25, BlendMode::default());
+/// ``````
+/// 
+/// See [`overlay`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_masked<B, F, M>(
+    bottom: &mut B, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    mask: &M, 
+    mask_x: i64, 
+    mask_y: i64, 
+    blend_mode: BlendMode
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    M: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_masked_with_options(bottom, top, x, y, mask, mask_x, mask_y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with options.  
+/// 
+/// See [`overlay_masked`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_masked_with_options<B, F, M>(
+    bottom: &mut B, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    mask: &M, 
+    mask_x: i64, 
+    mask_y: i64, 
+    options: OverlayOptions
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    M: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_masked_impl(bottom, top, x, y, mask.dimensions(), mask_x, mask_y, options, |mask_x, mask_y| {
+        #[cfg(debug_assertions)] {
+            mask_value(&mask.get_pixel(mask_x, mask_y))
+        }
+
+        #[cfg(not(debug_assertions))] unsafe {
+            mask_value(&mask.unsafe_get_pixel(mask_x, mask_y))
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn overlay_masked_impl<B, F, C>(
+    bottom: &mut B, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    mask_dimensions: (u32, u32), 
+    mask_x: i64, 
+    mask_y: i64, 
+    options: OverlayOptions,
+    mask: C,
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    C: Fn(u32, u32) -> Float,
+{
+    // Restrict the top image to the area covered by the mask.
+    let (
+        origin_top_x, 
+        origin_top_y, 
+        origin_mask_x, 
+        origin_mask_y, 
+        range_width, 
+        range_height
+    ) = overlay_bounds_ext(top.dimensions(), mask_dimensions, mask_x, mask_y);
+
+    let top = top.view(origin_top_x, origin_top_y, range_width, range_height);
+    let x = x.saturating_add(i64::from(origin_top_x));
+    let y = y.saturating_add(i64::from(origin_top_y));
+
+    overlay_impl(bottom, &*top, x, y, options, |fg_x, fg_y| {
+        mask(origin_mask_x + fg_x, origin_mask_y + fg_y)
+    })
+}
+
+/// Coverage of a mask pixel. (0.0 <= value <= 1.0)
+#[inline(always)]
+fn mask_value<P: AsRgba>(pixel: &P) -> Float {
+    let [r, g, b, a] = pixel.to_rgba();
+    rgb_to_luma(r, g, b) * a
+}
+
+/// `coverage` is called with the coordinate of the top pixel and scales its opacity.
+fn overlay_impl<B, F, C>(bottom: &mut B, top: &F, x: i64, y: i64, options: OverlayOptions, coverage: C) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    C: Fn(u32, u32) -> Float,
+{
+    blend_mode_map!(options.blend_mode, BlendParams::from_options(&options), top.dimensions(), (B::Pixel, F::Pixel), row_blender => {
+        overlay_rows(bottom, top, x, y, options, coverage, row_blender)
+    })
+}
+
+/// `row_blender` creates the blend function for a row of the top image.
+fn overlay_rows<B, F, C, R, L>(bottom: &mut B, top: &F, x: i64, y: i64, options: OverlayOptions, coverage: C, row_blender: R) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+    C: Fn(u32, u32) -> Float,
+    R: Fn(u32) -> L,
+    L: FnMut(&mut B::Pixel, &F::Pixel, &BlendParams),
+{
+    let params = BlendParams::from_options(&options);
+    if params.opacity <= 0. {
+        return;
     }
 
-    
-    use crate::blend::*;
+    let (
+        origin_bottom_x, 
+        origin_bottom_y, 
+        origin_top_x, 
+        origin_top_y, 
+        range_width, 
+        range_height
+    ) = overlay_bounds_ext(bottom.dimensions(), top.dimensions(), x, y);
+
+    for y in 0..range_height {
+        let (bg_y, fg_y) = (origin_bottom_y + y, origin_top_y + y);
+        // Only the blender for dissolve has a state.
+        #[allow(unused_mut)]
+        let mut blend = row_blender(fg_y);
+
+        for x in 0..range_width {
+            let (bg_x, fg_x) = (origin_bottom_x + x, origin_top_x + x);
+
+            let mut pixel_params = params;
+            pixel_params.opacity *= coverage(fg_x, fg_y);
+
+            #[cfg(debug_assertions)] {
+                let (mut bg, fg) = (
+                    bottom.get_pixel(bg_x, bg_y),
+                    top.get_pixel(fg_x, fg_y),
+                );
+
+                blend(&mut bg, &fg, &pixel_params);
+
+                bottom.put_pixel(bg_x, bg_y, bg);
+            }
+            
+            #[cfg(not(debug_assertions))] unsafe {
+                let (mut bg, fg) = (
+                    bottom.unsafe_get_pixel(bg_x, bg_y),
+                    top.unsafe_get_pixel(fg_x, fg_y),
+                );
 
-    match blend_mode {
-        #[cfg(feature = "blend_dissolve")]
-        BlendMode::Dissolve => {
-            // Ensure the same state if the top image is the same.
-            let ref mut rng = FastUnsecurePrng::new(top.width(), top.height());
+                blend(&mut bg, &fg, &pixel_params);
 
-            overlay!((bg, fg) => blend_dissolve(bg, fg, rng));
+                bottom.unsafe_put_pixel(bg_x, bg_y, bg);
+            }
         }
-        BlendMode::Normal => overlay!((bg, fg) => blend_normal(bg, fg)),
-        BlendMode::Darken => overlay!((bg, fg) => blend_darken(bg, fg)),
-        BlendMode::Multiply => overlay!((bg, fg) => blend_multiply(bg, fg)),
-        BlendMode::ColorBurn => overlay!((bg, fg) => blend_color_burn(bg, fg)),
-        BlendMode::LinearBurn => overlay!((bg, fg) => blend_linear_burn(bg, fg)),
-        BlendMode::Lighten => overlay!((bg, fg) => blend_lighten(bg, fg)),
-        BlendMode::Screen => overlay!((bg, fg) => blend_screen(bg, fg)),
-        BlendMode::ColorDodge => overlay!((bg, fg) => blend_color_dodge(bg, fg)),
-        BlendMode::LinearDodge => overlay!((bg, fg) => blend_linear_dodge(bg, fg)),
-        BlendMode::Overlay => overlay!((bg, fg) => blend_overlay(bg, fg)),
-        BlendMode::SoftLight => overlay!((bg, fg) => blend_soft_light(bg, fg)),
-        BlendMode::HardLight => overlay!((bg, fg) => blend_hard_light(bg, fg)),
-        BlendMode::VividLight => overlay!((bg, fg) => blend_vivid_light(bg, fg)),
-        BlendMode::LinearLight => overlay!((bg, fg) => blend_linear_light(bg, fg)),
-        BlendMode::PinLight => overlay!((bg, fg) => blend_pin_light(bg, fg)),
-        BlendMode::HardMix => overlay!((bg, fg) => blend_hard_mix(bg, fg)),
-        BlendMode::Difference => overlay!((bg, fg) => blend_difference(bg, fg)),
-        BlendMode::Exclusion => overlay!((bg, fg) => blend_exclusion(bg, fg)),
-        BlendMode::Subtract => overlay!((bg, fg) => blend_subtract(bg, fg)),
-        BlendMode::Divide => overlay!((bg, fg) => blend_divide(bg, fg)),
-        BlendMode::DarkerColor => overlay!((bg, fg) => blend_darker_color(bg, fg)),
-        BlendMode::LighterColor => overlay!((bg, fg) => blend_lighter_color(bg, fg)),
-        BlendMode::Hue => overlay!((bg, fg) => blend_hue(bg, fg)),
-        BlendMode::Saturation => overlay!((bg, fg) => blend_saturation(bg, fg)),
-        BlendMode::Color => overlay!((bg, fg) => blend_color(bg, fg)),
-        BlendMode::Luminosity => overlay!((bg, fg) => blend_luminosity(bg, fg)),
     }
 }
 
+/// Overlays the pixels returned by `sample` onto a region of the bottom image.  
+/// 
+/// `sample` is called with the coordinate of the bottom pixel, 
+/// and returns the color of the top pixel, in the alpha mode of the options, and its coverage.
+fn overlay_sampled<B, S>(bottom: &mut B, (left, top, width, height): (u32, u32, u32, u32), options: OverlayOptions, sample: S)
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    S: Fn(u32, u32) -> ([Float; 4], Float),
+{
+    let params = BlendParams::from_options(&options);
+    if params.opacity <= 0. {
+        return;
+    }
+
+    blend_mode_map!(options.blend_mode, params, (width, height), (B::Pixel, Rgba<Float>), row_blender => {
+        for y in 0..height {
+            let bg_y = top + y;
+            // Only the blender for dissolve has a state.
+            #[allow(unused_mut)]
+            let mut blend = row_blender(y);
+
+            for x in 0..width {
+                let bg_x = left + x;
+
+                let (fg, coverage) = sample(bg_x, bg_y);
+                if coverage <= 0. {
+                    continue;
+                }
+
+                let mut pixel_params = params;
+                pixel_params.opacity *= coverage;
+
+                #[cfg(debug_assertions)] {
+                    let mut bg = bottom.get_pixel(bg_x, bg_y);
+                    blend(&mut bg, &Rgba(fg), &pixel_params);
+                    bottom.put_pixel(bg_x, bg_y, bg);
+                }
+                
+                #[cfg(not(debug_assertions))] unsafe {
+                    let mut bg = bottom.unsafe_get_pixel(bg_x, bg_y);
+                    blend(&mut bg, &Rgba(fg), &pixel_params);
+                    bottom.unsafe_put_pixel(bg_x, bg_y, bg);
+                }
+            }
+        }
+    })
+}
+
 /// Overlay an image at a given coordinate (x, y) with blend mode. 
 ///  
 /// See [`overlay`] for details.
@@ -142,11 +655,7 @@ pub fn overlay_dyn_img(
     blend_mode: BlendMode
 ) {
 
-    dynamic_map!(bottom, bottom, {
-        dynamic_map!(top, top, {
-            overlay(bottom, top, x, y, blend_mode);
-        })
-    })
+    overlay_dyn_img_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
 }
 
 /// Overlay an image at a given coordinate (x, y) with blend mode. 
@@ -163,9 +672,7 @@ where
     B: GenericImage::<Pixel: AsRgba> 
 {
 
-    dynamic_map!(top, top, {
-        overlay(bottom, top, x, y, blend_mode);
-    })
+    overlay_dyn_img_to_img_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
 }
 
 /// Overlay an image at a given coordinate (x, y) with blend mode. 
@@ -182,11 +689,1015 @@ where
     F: GenericImageView::<Pixel: AsRgba>,
 {
 
+    overlay_img_to_dyn_img_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_dyn_img_with_options`].  
+/// 
+/// See [`overlay_with_options`] for details.
+pub fn overlay_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_dyn_img_with_options(bottom, top, x, y, options))
+}
+
+/// Overlay an image at a given coordinate (x, y) with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+/// 
+/// # Usage
+/// ``````
+/// use image::DynamicImage;
+/// use image_overlay::{try_overlay_dyn_img, BlendMode, OverlayError};
+/// 
+/// let mut dest = DynamicImage::new_rgba8(100, 100);
+/// let src = DynamicImage::new_luma8(100, 100);
+/// 
+/// try_overlay_dyn_img(&mut dest, &src, 0, 0, BlendMode::default())?;
+/// # Ok::<(), OverlayError>(())
+/// ``````
+///  
+/// See [`overlay`] for details.
+pub fn try_overlay_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_dyn_img_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options. 
+///  
+/// See [`try_overlay_dyn_img`] for details.
+pub fn try_overlay_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
     dynamic_map!(bottom, bottom, {
-        overlay(bottom, top, x, y, blend_mode);
+        dynamic_map!(top, top, {
+            overlay_buffer_with_options(bottom, top, x, y, options);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Overlay an image at a given coordinate (x, y) with options. 
+/// 
+/// The top image is converted to Rgba32F if its color type is not supported.  
+///  
+/// See [`overlay_with_options`] for details.
+pub fn overlay_dyn_img_to_img_with_options<B>(
+    bottom: &mut B, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64,
+    options: OverlayOptions
+) 
+where  
+    B: GenericImage::<Pixel: AsRgba> 
+{
+
+    let top = &*to_supported(top);
+
+    dynamic_map!(top, top, {
+        overlay_with_options(bottom, top, x, y, options);
     })
 }
 
+/// Overlay an image at a given coordinate (x, y) with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_img_to_dyn_img_with_options`].  
+/// 
+/// See [`overlay_with_options`] for details.
+pub fn overlay_img_to_dyn_img_with_options<F>(
+    bottom: &mut DynamicImage, 
+    top: &F, 
+    x: i64, 
+    y: i64,
+    options: OverlayOptions
+) 
+where  
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+
+    expect_supported(try_overlay_img_to_dyn_img_with_options(bottom, top, x, y, options))
+}
+
+/// Overlay an image at a given coordinate (x, y) with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.  
+///  
+/// See [`overlay`] for details.
+pub fn try_overlay_img_to_dyn_img<F>(
+    bottom: &mut DynamicImage, 
+    top: &F, 
+    x: i64, 
+    y: i64,
+    blend_mode: BlendMode
+) -> Result<(), OverlayError>
+where  
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+
+    try_overlay_img_to_dyn_img_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options. 
+///  
+/// See [`try_overlay_img_to_dyn_img`] for details.
+pub fn try_overlay_img_to_dyn_img_with_options<F>(
+    bottom: &mut DynamicImage, 
+    top: &F, 
+    x: i64, 
+    y: i64,
+    options: OverlayOptions
+) -> Result<(), OverlayError>
+where  
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+
+    let color_type = bottom.color();
+
+    dynamic_map!(bottom, bottom, {
+        overlay_with_options(bottom, top, x, y, options);
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with blend mode. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_masked_dyn_img`].  
+/// 
+/// See [`overlay_masked`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_masked_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    mask: &DynamicImage, 
+    mask_x: i64, 
+    mask_y: i64, 
+    blend_mode: BlendMode
+) {
+
+    overlay_masked_dyn_img_with_options(bottom, top, x, y, mask, mask_x, mask_y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_masked_dyn_img_with_options`].  
+/// 
+/// See [`overlay_masked`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_masked_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    mask: &DynamicImage, 
+    mask_x: i64, 
+    mask_y: i64, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_masked_dyn_img_with_options(bottom, top, x, y, mask, mask_x, mask_y, options))
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image and the mask are converted to Rgba32F if their color types are not supported.  
+///  
+/// See [`overlay_masked`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn try_overlay_masked_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    mask: &DynamicImage, 
+    mask_x: i64, 
+    mask_y: i64, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_masked_dyn_img_with_options(bottom, top, x, y, mask, mask_x, mask_y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) through a mask with options. 
+///  
+/// See [`try_overlay_masked_dyn_img`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn try_overlay_masked_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    mask: &DynamicImage, 
+    mask_x: i64, 
+    mask_y: i64, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
+    // Dispatching the mask dynamically avoids instantiating every combination of three images.
+    // Masks of the other color types are read through an Rgba32F copy.
+    let converted;
+    let mask_fn: Box<dyn Fn(u32, u32) -> Float + '_> = dynamic_map!(mask, mask, {
+        Box::new(|mask_x, mask_y| mask_value(mask.get_pixel(mask_x, mask_y)))
+    }, {
+        converted = mask.to_rgba32f();
+        Box::new(|mask_x, mask_y| mask_value(converted.get_pixel(mask_x, mask_y)))
+    });
+    let mask_fn = &*mask_fn;
+
+    dynamic_map!(bottom, bottom, {
+        dynamic_map!(top, top, {
+            overlay_masked_impl(bottom, top, x, y, mask.dimensions(), mask_x, mask_y, options, mask_fn);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Overlay an image at a given coordinate (x, y) with layer effects and options.  
+/// 
+/// The result is the same as [`overlay_with_options`] if no effects are enabled.
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_with_effects, BlendMode, Effects, Shadow, Stroke, StrokePosition};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(50, 50);
+/// 
+/// let effects = Effects::new()
+///     .with_drop_shadow(Shadow::drop_shadow().with_offset(4, 8).with_blur(6.))
+///     .with_outer_glow(Shadow::outer_glow())
+///     .with_stroke(Stroke::new(3., [1., 1., 1.]).with_position(StrokePosition::Center));
+/// 
+/// overlay_with_effects(&mut dest, &src, 25, 25, &effects, BlendMode::Normal.into());
+/// ``````
+/// 
+/// See [`Effects`] and [`overlay`] for details.
+pub fn overlay_with_effects<B, F>(bottom: &mut B, top: &F, x: i64, y: i64, effects: &Effects, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    effects.draw_below(bottom, top, x, y, options);
+    overlay_with_options(bottom, top, x, y, options);
+    effects.draw_above(bottom, top, x, y, options);
+}
+
+/// Overlay an image at a given coordinate (x, y) with layer effects and options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_dyn_img_with_effects`].  
+/// 
+/// See [`overlay_with_effects`] for details.
+pub fn overlay_dyn_img_with_effects(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    effects: &Effects, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_dyn_img_with_effects(bottom, top, x, y, effects, options))
+}
+
+/// Overlay an image at a given coordinate (x, y) with layer effects and options. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+///  
+/// See [`overlay_with_effects`] for details.
+pub fn try_overlay_dyn_img_with_effects(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    effects: &Effects, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
+    dynamic_map!(&mut *bottom, bottom, {
+        dynamic_map!(top, top, {
+            effects.draw_below(bottom, top, x, y, options);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    try_overlay_dyn_img_with_options(bottom, top, x, y, options)?;
+
+    dynamic_map!(bottom, bottom, {
+        dynamic_map!(top, top, {
+            effects.draw_above(bottom, top, x, y, options);
+        })
+    });
+
+    Ok(())
+}
+
+/// Overlay an image at a position given by a placement with blend mode.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_placed, Anchor, BlendMode, Placement};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(20, 20);
+/// 
+/// overlay_placed(&mut dest, &src, Placement::new(Anchor::BottomRight).with_margin(16, 16), BlendMode::default());
+/// ``````
+/// 
+/// See [`Placement`] and [`overlay`] for details.
+pub fn overlay_placed<B, F>(bottom: &mut B, top: &F, placement: Placement, blend_mode: BlendMode) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_placed_with_options(bottom, top, placement, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a position given by a placement with options.  
+/// 
+/// See [`overlay_placed`] for details.
+pub fn overlay_placed_with_options<B, F>(bottom: &mut B, top: &F, placement: Placement, options: OverlayOptions) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    let (x, y) = placement.position(bottom.dimensions(), top.dimensions());
+    overlay_with_options(bottom, top, x, y, options)
+}
+
+/// Overlay an image at a position given by a placement with blend mode. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_placed_dyn_img`].  
+/// 
+/// See [`overlay_placed`] for details.
+pub fn overlay_placed_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    placement: Placement, 
+    blend_mode: BlendMode
+) {
+
+    overlay_placed_dyn_img_with_options(bottom, top, placement, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a position given by a placement with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_placed_dyn_img_with_options`].  
+/// 
+/// See [`overlay_placed`] for details.
+pub fn overlay_placed_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    placement: Placement, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_placed_dyn_img_with_options(bottom, top, placement, options))
+}
+
+/// Overlay an image at a position given by a placement with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+///  
+/// See [`overlay_placed`] for details.
+pub fn try_overlay_placed_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    placement: Placement, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_placed_dyn_img_with_options(bottom, top, placement, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a position given by a placement with options. 
+///  
+/// See [`try_overlay_placed_dyn_img`] for details.
+pub fn try_overlay_placed_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    placement: Placement, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let (x, y) = placement.position(bottom.dimensions(), top.dimensions());
+    try_overlay_dyn_img_with_options(bottom, top, x, y, options)
+}
+
+/// Overlay an image transformed by an affine transformation with blend mode.  
+/// 
+/// `transform` maps the coordinates of the top image to those of the bottom image,
+/// where pixel (x, y) covers [x, x + 1) x [y, y + 1).
+/// The top image is sampled with `interpolation`, and its edges are antialiased by coverage.  
+/// Only the bounding box of the transformed top image is processed.  
+/// Nothing is drawn if the transformation is not invertible.
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_transformed, Affine, BlendMode, Interpolation};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(40, 20);
+/// 
+/// // Half the size, rotated by 30 degrees and placed at (10.5, 20.25).
+/// let transform = Affine::scaling(0.5, 0.5)
+///     .then(Affine::rotation(30f32.to_radians() as _))
+///     .then(Affine::translation(10.5, 20.25));
+/// 
+/// overlay_transformed(&mut dest, &src, transform, Interpolation::Bicubic, BlendMode::default());
+/// ``````
+/// 
+/// See [`overlay`] for details.
+pub fn overlay_transformed<B, F>(
+    bottom: &mut B, 
+    top: &F, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    blend_mode: BlendMode
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_transformed_with_options(bottom, top, transform, interpolation, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image transformed by an affine transformation with options.  
+/// 
+/// See [`overlay_transformed`] for details.
+pub fn overlay_transformed_with_options<B, F>(
+    bottom: &mut B, 
+    top: &F, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    options: OverlayOptions
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    let (top_width, top_height) = top.dimensions();
+    if top_width == 0 || top_height == 0 {
+        return;
+    }
+    let Some(inverse) = transform.inverse() else {
+        return;
+    };
+
+    let region = transformed_bounds(bottom.dimensions(), (top_width, top_height), transform);
+    let premultiplied = options.alpha_mode == AlphaMode::Premultiplied;
+
+    // Distances to the edges of the top image are measured in bottom pixels.
+    let [[a, b, _], [d, e, _]] = inverse.matrix;
+    let (scale_x, scale_y) = (a.hypot(b).recip(), d.hypot(e).recip());
+    let (top_width, top_height) = (top_width as Float, top_height as Float);
+
+    overlay_sampled(bottom, region, options, |x, y| {
+        let (u, v) = inverse.apply(x as Float + 0.5, y as Float + 0.5);
+
+        let edge = |distance: Float| (distance + 0.5).clamp(0., 1.);
+        let coverage = edge(u * scale_x)
+            * edge((top_width - u) * scale_x)
+            * edge(v * scale_y)
+            * edge((top_height - v) * scale_y);
+        if coverage <= 0. {
+            return ([0.; 4], 0.);
+        }
+
+        (interpolation.sample(top, u, v, premultiplied, options.hdr), coverage)
+    })
+}
+
+/// Returns the region of the bottom image covered by the transformed top image, 
+/// with half a pixel of margin for antialiasing.
+fn transformed_bounds(
+    (bottom_width, bottom_height): (u32, u32),
+    (top_width, top_height): (u32, u32),
+    transform: Affine,
+) -> (u32, u32, u32, u32) {
+    let (top_width, top_height) = (top_width as Float, top_height as Float);
+    let corners = [(0., 0.), (top_width, 0.), (0., top_height), (top_width, top_height)]
+        .map(|(x, y)| transform.apply(x, y));
+
+    let clip = |value: Float, max: u32| value.clamp(0., max as Float) as u32;
+    let min_x = corners.iter().map(|c| c.0).fold(Float::INFINITY, Float::min);
+    let max_x = corners.iter().map(|c| c.0).fold(Float::NEG_INFINITY, Float::max);
+    let min_y = corners.iter().map(|c| c.1).fold(Float::INFINITY, Float::min);
+    let max_y = corners.iter().map(|c| c.1).fold(Float::NEG_INFINITY, Float::max);
+
+    let (left, right) = (clip((min_x - 0.5).floor(), bottom_width), clip((max_x + 0.5).ceil(), bottom_width));
+    let (top, bottom) = (clip((min_y - 0.5).floor(), bottom_height), clip((max_y + 0.5).ceil(), bottom_height));
+
+    (left, top, right - left, bottom - top)
+}
+
+/// Overlay an image transformed by an affine transformation with blend mode. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_transformed_dyn_img`].  
+/// 
+/// See [`overlay_transformed`] for details.
+pub fn overlay_transformed_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    blend_mode: BlendMode
+) {
+
+    overlay_transformed_dyn_img_with_options(bottom, top, transform, interpolation, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image transformed by an affine transformation with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_transformed_dyn_img_with_options`].  
+/// 
+/// See [`overlay_transformed`] for details.
+pub fn overlay_transformed_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_transformed_dyn_img_with_options(bottom, top, transform, interpolation, options))
+}
+
+/// Overlay an image transformed by an affine transformation with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+///  
+/// See [`overlay_transformed`] for details.
+pub fn try_overlay_transformed_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_transformed_dyn_img_with_options(bottom, top, transform, interpolation, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image transformed by an affine transformation with options. 
+///  
+/// See [`try_overlay_transformed_dyn_img`] for details.
+pub fn try_overlay_transformed_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    transform: Affine, 
+    interpolation: Interpolation, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
+    dynamic_map!(bottom, bottom, {
+        dynamic_map!(top, top, {
+            overlay_transformed_with_options(bottom, top, transform, interpolation, options);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with blend mode.  
+/// 
+/// The rectangle starts at (x, y) and is clipped to the bottom image.
+/// `tiling` decides how the pattern repeats along each axis and where it starts.
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_tiled, BlendMode, Tiling, WrapMode};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(16, 16);
+/// 
+/// let tiling = Tiling::new(WrapMode::MirrorRepeat, WrapMode::MirrorRepeat).with_phase(8, 8);
+/// overlay_tiled(&mut dest, &src, 0, 0, 100, 100, tiling, BlendMode::Multiply);
+/// ``````
+/// 
+/// See [`overlay`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_tiled<B, F>(
+    bottom: &mut B, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    blend_mode: BlendMode
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    overlay_tiled_with_options(bottom, top, x, y, width, height, tiling, OverlayOptions::new(blend_mode))
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with options.  
+/// 
+/// See [`overlay_tiled`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_tiled_with_options<B, F>(
+    bottom: &mut B, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    options: OverlayOptions
+) 
+where 
+    B: GenericImage::<Pixel: AsRgba>,
+    F: GenericImageView::<Pixel: AsRgba>,
+{
+    let (top_width, top_height) = top.dimensions();
+    if top_width == 0 || top_height == 0 {
+        return;
+    }
+
+    let (left, top_y, _, _, range_width, range_height) = overlay_bounds_ext(bottom.dimensions(), (width, height), x, y);
+    let region = (left, top_y, range_width, range_height);
+    let phase_x = tiling.wrap_x.reduce(tiling.phase_x, top_width);
+    let phase_y = tiling.wrap_y.reduce(tiling.phase_y, top_height);
+
+    // The region is inside the rectangle, so the coordinates in it are less than its size.
+    overlay_sampled(bottom, region, options, |bg_x, bg_y| {
+        let u = tiling.wrap_x.wrap((i64::from(bg_x) - x).saturating_add(phase_x), top_width);
+        let v = tiling.wrap_y.wrap((i64::from(bg_y) - y).saturating_add(phase_y), top_height);
+        let (Some(u), Some(v)) = (u, v) else {
+            return ([0.; 4], 0.);
+        };
+
+        #[cfg(debug_assertions)] {
+            (AsRgba::to_rgba(&top.get_pixel(u, v)), 1.)
+        }
+
+        #[cfg(not(debug_assertions))] unsafe {
+            (AsRgba::to_rgba(&top.unsafe_get_pixel(u, v)), 1.)
+        }
+    })
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with blend mode. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_tiled_dyn_img`].  
+/// 
+/// See [`overlay_tiled`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_tiled_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    blend_mode: BlendMode
+) {
+
+    overlay_tiled_dyn_img_with_options(bottom, top, x, y, width, height, tiling, OverlayOptions::new(blend_mode))
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with options. 
+///  
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_tiled_dyn_img_with_options`].  
+/// 
+/// See [`overlay_tiled`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn overlay_tiled_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_tiled_dyn_img_with_options(bottom, top, x, y, width, height, tiling, options))
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with blend mode. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+///  
+/// See [`overlay_tiled`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn try_overlay_tiled_dyn_img(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_tiled_dyn_img_with_options(bottom, top, x, y, width, height, tiling, OverlayOptions::new(blend_mode))
+}
+
+/// Fill a rectangle of the bottom image with the top image repeated as a pattern, with options. 
+///  
+/// See [`try_overlay_tiled_dyn_img`] for details.
+#[allow(clippy::too_many_arguments)]
+pub fn try_overlay_tiled_dyn_img_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    width: u32, 
+    height: u32, 
+    tiling: Tiling, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
+    dynamic_map!(bottom, bottom, {
+        dynamic_map!(top, top, {
+            overlay_tiled_with_options(bottom, top, x, y, width, height, tiling, options);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Overlay an image at a given coordinate (x, y) with blend mode, processing rows in parallel.  
+/// 
+/// The result is the same as [`overlay`], including `BlendMode::Dissolve`.  
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_par, BlendMode};
+/// 
+/// let mut dest = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// let src = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(100, 100);
+/// 
+/// overlay_par(&mut dest, &src, 0, 0, BlendMode::default());
+/// ``````
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+/// 
+/// See [`overlay`] for details.
+#[cfg(feature = "rayon")]
+pub fn overlay_par<P, Container, F>(
+    bottom: &mut ImageBuffer<P, Container>, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) 
+where 
+    P: Pixel<Subpixel: Send> + AsRgba,
+    Container: DerefMut<Target = [P::Subpixel]>,
+    F: GenericImageView::<Pixel: AsRgba> + Sync,
+{
+    overlay_par_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options, processing rows in parallel.  
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+/// 
+/// See [`overlay_par`] for details.
+#[cfg(feature = "rayon")]
+pub fn overlay_par_with_options<P, Container, F>(
+    bottom: &mut ImageBuffer<P, Container>, 
+    top: &F, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) 
+where 
+    P: Pixel<Subpixel: Send> + AsRgba,
+    Container: DerefMut<Target = [P::Subpixel]>,
+    F: GenericImageView::<Pixel: AsRgba> + Sync,
+{
+    let params = BlendParams::from_options(&options);
+    if params.opacity <= 0. {
+        return;
+    }
+
+    let (
+        origin_bottom_x, 
+        origin_bottom_y, 
+        origin_top_x, 
+        origin_top_y, 
+        range_width, 
+        range_height
+    ) = overlay_bounds_ext(bottom.dimensions(), top.dimensions(), x, y);
+    if range_width == 0 || range_height == 0 {
+        return;
+    }
+
+    let channels = usize::from(P::CHANNEL_COUNT);
+    let row_len = bottom.width() as usize * channels;
+    let (start, end) = (
+        origin_bottom_x as usize * channels,
+        (origin_bottom_x + range_width) as usize * channels,
+    );
+
+    blend_mode_map!(options.blend_mode, params, top.dimensions(), (P, F::Pixel), row_blender => {
+        bottom
+            .par_chunks_exact_mut(row_len)
+            .skip(origin_bottom_y as usize)
+            .take(range_height as usize)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let fg_y = origin_top_y + y as u32;
+
+                // Only the blender for dissolve has a state.
+                #[allow(unused_mut)]
+                let mut blend = row_blender(fg_y);
+
+                for (x, bg) in row[start..end].chunks_exact_mut(channels).enumerate() {
+                    let fg_x = origin_top_x + x as u32;
+
+                    #[cfg(debug_assertions)]
+                    let fg = top.get_pixel(fg_x, fg_y);
+
+                    #[cfg(not(debug_assertions))]
+                    let fg = unsafe { top.unsafe_get_pixel(fg_x, fg_y) };
+
+                    blend(P::from_slice_mut(bg), &fg, &params);
+                }
+            })
+    })
+}
+
+/// Overlay an image at a given coordinate (x, y) with blend mode, processing rows in parallel. 
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+///  
+/// See [`overlay_par`] for details.
+#[cfg(feature = "rayon")]
+pub fn overlay_dyn_img_par(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) {
+
+    overlay_dyn_img_par_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options, processing rows in parallel. 
+/// 
+/// # Panics
+/// Panics if the color type of the bottom image is not supported. See [`try_overlay_dyn_img_par_with_options`].  
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+///  
+/// See [`overlay_par`] for details.
+#[cfg(feature = "rayon")]
+pub fn overlay_dyn_img_par_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) {
+
+    expect_supported(try_overlay_dyn_img_par_with_options(bottom, top, x, y, options))
+}
+
+/// Overlay an image at a given coordinate (x, y) with blend mode, processing rows in parallel. 
+/// 
+/// Returns an error if the color type of the bottom image is not supported.
+/// The top image is converted to Rgba32F if its color type is not supported.  
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+///  
+/// See [`overlay_par`] for details.
+#[cfg(feature = "rayon")]
+pub fn try_overlay_dyn_img_par(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    blend_mode: BlendMode
+) -> Result<(), OverlayError> {
+
+    try_overlay_dyn_img_par_with_options(bottom, top, x, y, OverlayOptions::new(blend_mode))
+}
+
+/// Overlay an image at a given coordinate (x, y) with options, processing rows in parallel. 
+/// 
+/// # Features
+/// This function is available only if "rayon" feature is enabled.
+///  
+/// See [`try_overlay_dyn_img_par`] for details.
+#[cfg(feature = "rayon")]
+pub fn try_overlay_dyn_img_par_with_options(
+    bottom: &mut DynamicImage, 
+    top: &DynamicImage, 
+    x: i64, 
+    y: i64, 
+    options: OverlayOptions
+) -> Result<(), OverlayError> {
+
+    let color_type = bottom.color();
+    let top = &*to_supported(top);
+
+    dynamic_map!(bottom, bottom, {
+        dynamic_map!(top, top, {
+            overlay_par_with_options(bottom, top, x, y, options);
+        })
+    }, return Err(OverlayError::UnsupportedColorType(color_type)));
+
+    Ok(())
+}
+
+/// Converts the image to Rgba32F if its color type is not supported.
+fn to_supported(image: &DynamicImage) -> Cow<'_, DynamicImage> {
+    match image {
+        DynamicImage::ImageLuma8(_)
+        | DynamicImage::ImageLuma16(_)
+        | DynamicImage::ImageLumaA8(_)
+        | DynamicImage::ImageLumaA16(_)
+        | DynamicImage::ImageRgb8(_)
+        | DynamicImage::ImageRgb16(_)
+        | DynamicImage::ImageRgb32F(_)
+        | DynamicImage::ImageRgba8(_)
+        | DynamicImage::ImageRgba16(_)
+        | DynamicImage::ImageRgba32F(_) => Cow::Borrowed(image),
+        _ => Cow::Owned(DynamicImage::ImageRgba32F(image.to_rgba32f())),
+    }
+}
+
+#[track_caller]
+fn expect_supported(result: Result<(), OverlayError>) {
+    if let Err(err) = result {
+        panic!("{}", err);
+    }
+}
+
 
 /// -------------------------------------------------------
 /// THIS FUNCTION IS COPIED FROM image crate (ver. 0.25.5)
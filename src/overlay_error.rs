@@ -0,0 +1,28 @@
+use std::fmt;
+use image::ColorType;
+
+
+/// An error returned by the `try_overlay_*` functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverlayError {
+
+    /// The color type of the bottom image is not supported.  
+    /// 
+    /// Top images and masks of unsupported color types are converted to Rgba32F instead,
+    /// but bottom images can not be written back through the conversion.
+    UnsupportedColorType(ColorType),
+}
+
+impl fmt::Display for OverlayError {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverlayError::UnsupportedColorType(color_type) => {
+                write!(f, "unsupported color type of the bottom image: {:?}", color_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OverlayError {}
@@ -0,0 +1,122 @@
+use crate::{as_rgba::Float, AlphaMode, BlendIf, BlendMode, ChannelMask, CompositeOperator};
+
+
+/// Options for overlaying an image.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{BlendMode, CompositeOperator, OverlayOptions};
+/// 
+/// let options = OverlayOptions::new(BlendMode::Multiply)
+///     .with_opacity(0.5)
+///     .with_composite_operator(CompositeOperator::SourceAtop);
+/// 
+/// assert_eq!(options.blend_mode, BlendMode::Multiply);
+/// assert_eq!(options.opacity, 0.5);
+/// ``````
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverlayOptions {
+    pub blend_mode: BlendMode,
+
+    /// Opacity of the top image. (0.0 <= value <= 1.0)  
+    /// Values out of range are clamped.
+    pub opacity: Float,
+
+    pub composite_operator: CompositeOperator,
+
+    /// Blends in linear light by decoding sRGB before blending and encoding it afterward,
+    /// like "Blend RGB Colors Using Gamma 1.0" of Photoshop.  
+    /// Alpha is not affected.
+    pub blend_in_linear: bool,
+
+    pub alpha_mode: AlphaMode,
+
+    /// Keeps values above 1.0 for floating point images (e.g. `Rgba<f32>`).  
+    /// 
+    /// `LinearDodge`, `Divide`, `GrainExtract`, `GrainMerge`, `Hue`, `Saturation`, `Color`, `Luminosity`
+    /// and the LCh modes drop their upper clamp, and still never go below 0.0.  
+    /// `Normal`, `Darken`, `Multiply`, `Lighten`, `Difference`, `Subtract`, `DarkerColor`, `LighterColor`, `Add` and `Average`
+    /// have no upper clamp in the first place.  
+    /// The other modes, such as `Screen` and `ColorDodge`, assume values in [0.0, 1.0],
+    /// so the colors are clamped to that range before they are passed to the blend mode.
+    /// Only the blended color is clamped, and the rest of the compositing uses the original colors.
+    /// See [`BlendMode::supports_hdr`].  
+    /// 
+    /// Integer images are still clamped when written back,
+    /// so this is meant for floating point images.
+    pub hdr: bool,
+
+    /// Shows the top image only where the pixels fall in the ranges. See [`BlendIf`].
+    pub blend_if: Option<BlendIf>,
+
+    /// Channels of the bottom image to write to. See [`ChannelMask`].
+    pub channel_mask: ChannelMask,
+}
+
+impl OverlayOptions {
+
+    pub const fn new(blend_mode: BlendMode) -> Self {
+        Self {
+            blend_mode,
+            opacity: 1.,
+            composite_operator: CompositeOperator::SourceOver,
+            blend_in_linear: false,
+            alpha_mode: AlphaMode::Straight,
+            hdr: false,
+            blend_if: None,
+            channel_mask: ChannelMask::ALL,
+        }
+    }
+
+    pub const fn with_opacity(mut self, opacity: Float) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub const fn with_composite_operator(mut self, composite_operator: CompositeOperator) -> Self {
+        self.composite_operator = composite_operator;
+        self
+    }
+
+    pub const fn with_blend_in_linear(mut self, blend_in_linear: bool) -> Self {
+        self.blend_in_linear = blend_in_linear;
+        self
+    }
+
+    pub const fn with_alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    pub const fn with_hdr(mut self, hdr: bool) -> Self {
+        self.hdr = hdr;
+        self
+    }
+
+    pub const fn with_blend_if(mut self, blend_if: BlendIf) -> Self {
+        self.blend_if = Some(blend_if);
+        self
+    }
+
+    pub const fn with_channel_mask(mut self, channel_mask: ChannelMask) -> Self {
+        self.channel_mask = channel_mask;
+        self
+    }
+}
+
+impl Default for OverlayOptions {
+
+    fn default() -> Self {
+        Self::new(BlendMode::default())
+    }
+}
+
+impl From<BlendMode> for OverlayOptions {
+
+    fn from(blend_mode: BlendMode) -> Self {
+        Self::new(blend_mode)
+    }
+}
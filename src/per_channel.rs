@@ -0,0 +1,37 @@
+use crate::{as_rgba::Float, Blender};
+
+
+/// A [`Blender`] which applies a formula to each of the red, green and blue channels independently.  
+/// 
+/// The formula takes the channel of the bottom and the channel of the top. (0.0 <= value <= 1.0)
+/// 
+/// # Usage
+/// ``````
+/// use image::{ImageBuffer, Rgba};
+/// use image_overlay::{overlay_with, Float, OverlayOptions, PerChannel};
+/// 
+/// let mut dest = ImageBuffer::from_pixel(1, 1, Rgba([0.5f32, 0.5, 0.5, 1.]));
+/// let src = ImageBuffer::from_pixel(1, 1, Rgba([0.5f32, 0.25, 1., 1.]));
+/// 
+/// // Same as BlendMode::Multiply.
+/// let multiply = PerChannel(|base: Float, blend: Float| base * blend);
+/// overlay_with(&mut dest, &src, 0, 0, &multiply, OverlayOptions::default());
+/// 
+/// assert_eq!(dest.get_pixel(0, 0), &Rgba([0.25, 0.125, 0.5, 1.]));
+/// ``````
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PerChannel<F>(pub F);
+
+impl<F> Blender for PerChannel<F>
+where 
+    F: Fn(Float, Float) -> Float,
+{
+    #[inline(always)]
+    fn blend(&self, cb: (Float, Float, Float), cs: (Float, Float, Float)) -> (Float, Float, Float) {
+        (
+            (self.0)(cb.0, cs.0),
+            (self.0)(cb.1, cs.1),
+            (self.0)(cb.2, cs.2),
+        )
+    }
+}
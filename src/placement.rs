@@ -0,0 +1,85 @@
+use crate::{alignment::Alignment, as_rgba::Float, Anchor};
+
+
+/// Position of the top image relative to the bottom image.  
+/// 
+/// The top image is aligned to the `anchor`, moved inward by the margin from the anchored edges,
+/// and then moved by the percentage offset of the bottom size (positive is right and down).  
+/// 
+/// When centered, the top image is shifted to the top-left by half a pixel if the remaining space is odd.
+/// The same arithmetic applies to a top image larger than the bottom image, which is then clipped.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{Anchor, Placement};
+/// 
+/// let placement = Placement::new(Anchor::BottomRight).with_margin(16, 16);
+/// assert_eq!(placement.position((1920, 1080), (200, 100)), (1704, 964));
+/// 
+/// let placement = Placement::new(Anchor::Center).with_percent(0., -10.);
+/// assert_eq!(placement.position((101, 100), (10, 10)), (45, 35));
+/// ``````
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement {
+    pub anchor: Anchor,
+
+    /// Distance from the anchored left or right edge. It is ignored when centered horizontally.
+    pub margin_x: i64,
+
+    /// Distance from the anchored top or bottom edge. It is ignored when centered vertically.
+    pub margin_y: i64,
+
+    /// Offset in percent of the bottom width.
+    pub percent_x: Float,
+
+    /// Offset in percent of the bottom height.
+    pub percent_y: Float,
+}
+
+impl Placement {
+
+    pub const fn new(anchor: Anchor) -> Self {
+        Self {
+            anchor,
+            margin_x: 0,
+            margin_y: 0,
+            percent_x: 0.,
+            percent_y: 0.,
+        }
+    }
+
+    pub const fn with_margin(mut self, margin_x: i64, margin_y: i64) -> Self {
+        self.margin_x = margin_x;
+        self.margin_y = margin_y;
+        self
+    }
+
+    pub const fn with_percent(mut self, percent_x: Float, percent_y: Float) -> Self {
+        self.percent_x = percent_x;
+        self.percent_y = percent_y;
+        self
+    }
+
+    /// Returns the coordinate (x, y) of the top image on the bottom image.
+    pub fn position(self, (bottom_width, bottom_height): (u32, u32), (top_width, top_height): (u32, u32)) -> (i64, i64) {
+        let (align_x, align_y) = self.anchor.alignment();
+        (
+            position(align_x, bottom_width, top_width, self.margin_x, self.percent_x),
+            position(align_y, bottom_height, top_height, self.margin_y, self.percent_y),
+        )
+    }
+}
+
+fn position(alignment: Alignment, bottom_size: u32, top_size: u32, margin: i64, percent: Float) -> i64 {
+    let space = i64::from(bottom_size) - i64::from(top_size);
+    let aligned = match alignment {
+        Alignment::Start => margin,
+        Alignment::Center => space.div_euclid(2),
+        Alignment::End => space - margin,
+    };
+    let offset = (bottom_size as Float * percent / 100.).round() as i64;
+    aligned.saturating_add(offset)
+}
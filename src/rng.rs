@@ -33,6 +33,13 @@ impl FastUnsecurePrng {
     pub fn new(k1: u32, k2: u32) -> Self {
         Self(Mwc128XXA32::new(k1, k2))
     }
+
+    /// Construct an instance for a row of an image.  
+    /// 
+    /// Each row has an independent state, so rows can be processed in any order.
+    pub fn for_row(width: u32, height: u32, row: u32) -> Self {
+        Self::new(width.wrapping_mul(0x9E37_79B9) ^ height, row)
+    }
 }
 
 impl Rng for FastUnsecurePrng {
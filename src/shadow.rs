@@ -0,0 +1,96 @@
+use crate::{as_rgba::Float, BlendMode};
+
+
+/// A blurred and tinted copy of the alpha of a layer, drawn beneath it.  
+/// 
+/// It is used for both drop shadows and outer glows. See [`Effects`](crate::Effects).  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    /// Straight RGB color. (0.0 <= value <= 1.0)
+    pub color: [Float; 3],
+
+    /// Offset from the layer.
+    pub offset_x: i64,
+
+    /// Offset from the layer.
+    pub offset_y: i64,
+
+    /// Standard deviation of the Gaussian blur in pixels.  
+    /// The size of the blur is capped at 250 pixels, like the size of Photoshop.
+    pub blur: Float,
+
+    /// Part of the blur size which hardens into a solid edge, like the spread of Photoshop. (0.0 <= value <= 1.0)  
+    /// 
+    /// The size of the blur is `3 * blur`.
+    /// The layer is dilated by `spread` of it, and then blurred with a standard deviation of `(1 - spread) * blur`.
+    /// It has no effect without a blur.
+    pub spread: Float,
+
+    pub blend_mode: BlendMode,
+
+    /// Opacity of the shadow, multiplied by the opacity of the layer. (0.0 <= value <= 1.0)
+    pub opacity: Float,
+}
+
+impl Shadow {
+
+    /// Black shadow offset to the bottom right, blended with `BlendMode::Multiply`.
+    pub const fn drop_shadow() -> Self {
+        Self {
+            color: [0., 0., 0.],
+            offset_x: 5,
+            offset_y: 5,
+            blur: 3.,
+            spread: 0.,
+            blend_mode: BlendMode::Multiply,
+            opacity: 0.75,
+        }
+    }
+
+    /// Light yellow glow around the layer, blended with `BlendMode::Screen`.
+    pub const fn outer_glow() -> Self {
+        Self {
+            color: [1., 1., 0.75],
+            offset_x: 0,
+            offset_y: 0,
+            blur: 3.,
+            spread: 0.,
+            blend_mode: BlendMode::Screen,
+            opacity: 0.75,
+        }
+    }
+
+    pub const fn with_color(mut self, color: [Float; 3]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub const fn with_offset(mut self, offset_x: i64, offset_y: i64) -> Self {
+        self.offset_x = offset_x;
+        self.offset_y = offset_y;
+        self
+    }
+
+    pub const fn with_blur(mut self, blur: Float) -> Self {
+        self.blur = blur;
+        self
+    }
+
+    pub const fn with_spread(mut self, spread: Float) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub const fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    pub const fn with_opacity(mut self, opacity: Float) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
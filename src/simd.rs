@@ -0,0 +1,429 @@
+// Vectorized kernels for overlaying `Rgba<u8>` onto `Rgba<u8>`.
+//
+// The kernels compute the same formula as the `blend!` macro with f32 lanes,
+// so the output is within ±1 of the scalar path.
+// Pixels are split into R, G, B and A lanes, so each iteration blends 4 pixels with SSE2 and 8 pixels with AVX2.
+
+use crate::BlendMode;
+
+
+/// Blends a row of top pixels into a row of bottom pixels. (opacity: 0.0 <= value <= 1.0)
+/// Both rows are RGBA8 channel data of the same length.
+pub(crate) type RowBlender = fn(bg: &mut [u8], fg: &[u8], opacity: f32);
+
+/// Returns the vectorized row blender for the blend mode,
+/// or `None` if the blend mode or the CPU is not supported.
+pub(crate) fn rgba8_row_blender(blend_mode: BlendMode) -> Option<RowBlender> {
+    #[cfg(target_arch = "x86_64")] {
+        x86::row_blender(blend_mode, true)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))] {
+        let _ = blend_mode;
+        None
+    }
+}
+
+/// Same as [`rgba8_row_blender`], but never selects AVX2, so that SSE2 can be tested on any x86_64 CPU.
+pub(crate) fn rgba8_row_blender_sse2(blend_mode: BlendMode) -> Option<RowBlender> {
+    #[cfg(target_arch = "x86_64")] {
+        x86::row_blender(blend_mode, false)
+    }
+
+    #[cfg(not(target_arch = "x86_64"))] {
+        let _ = blend_mode;
+        None
+    }
+}
+
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+    use super::RowBlender;
+    use crate::BlendMode;
+
+    pub(super) fn row_blender(blend_mode: BlendMode, avx2: bool) -> Option<RowBlender> {
+        macro_rules! select {
+            ($kernel: ty) => {
+                if avx2 && is_x86_feature_detected!("avx2") {
+                    Some(row_avx2::<$kernel>)
+                }
+                else {
+                    Some(row_sse2::<$kernel>)
+                }
+            };
+        }
+
+        match blend_mode {
+            BlendMode::Normal => select!(Normal),
+            BlendMode::Multiply => select!(Multiply),
+            BlendMode::Screen => select!(Screen),
+            BlendMode::Overlay => select!(Overlay),
+            BlendMode::Darken => select!(Darken),
+            BlendMode::Lighten => select!(Lighten),
+            BlendMode::Difference => select!(Difference),
+            BlendMode::LinearDodge => select!(LinearDodge),
+            BlendMode::Subtract => select!(Subtract),
+            _ => None,
+        }
+    }
+
+    fn row_sse2<K: Kernel>(bg: &mut [u8], fg: &[u8], opacity: f32) {
+        // SAFETY: SSE2 is always available on x86_64.
+        unsafe { row::<F32x4, K>(bg, fg, opacity) }
+    }
+
+    fn row_avx2<K: Kernel>(bg: &mut [u8], fg: &[u8], opacity: f32) {
+        // SAFETY: This is selected only if AVX2 is detected.
+        unsafe { row_avx2_impl::<K>(bg, fg, opacity) }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn row_avx2_impl<K: Kernel>(bg: &mut [u8], fg: &[u8], opacity: f32) {
+        row::<F32x8, K>(bg, fg, opacity);
+    }
+
+    #[inline(always)]
+    unsafe fn row<V: Lanes, K: Kernel>(bg: &mut [u8], fg: &[u8], opacity: f32) {
+        let (opacity, one) = (V::splat(opacity), V::splat(1.));
+        let len = bg.len().min(fg.len()) / 4 * 4;
+        let (bg, fg) = (&mut bg[..len], &fg[..len]);
+
+        let mut bg_chunks = bg.chunks_exact_mut(V::BYTES);
+        let mut fg_chunks = fg.chunks_exact(V::BYTES);
+        for (bg, fg) in (&mut bg_chunks).zip(&mut fg_chunks) {
+            V::store(composite::<V, K>(V::load(bg), V::load(fg), opacity, one), bg);
+        }
+
+        // The last pixels are blended in zero-padded buffers.
+        let (bg_rest, fg_rest) = (bg_chunks.into_remainder(), fg_chunks.remainder());
+        if !bg_rest.is_empty() {
+            let (mut bg_buf, mut fg_buf) = ([0; 32], [0; 32]);
+            bg_buf[..bg_rest.len()].copy_from_slice(bg_rest);
+            fg_buf[..fg_rest.len()].copy_from_slice(fg_rest);
+
+            let out = composite::<V, K>(V::load(&bg_buf), V::load(&fg_buf), opacity, one);
+            V::store(out, &mut bg_buf);
+            bg_rest.copy_from_slice(&bg_buf[..bg_rest.len()]);
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn composite<V: Lanes, K: Kernel>(bg: [V; 4], fg: [V; 4], opacity: V, one: V) -> [V; 4] {
+        let bg_a = bg[3];
+        let fg_a = fg[3].mul(opacity);
+        let tmp = one.sub(fg_a);
+        let bg_weight = bg_a.mul(tmp);
+        let alpha_final = fg_a.add(bg_weight);
+
+        // Leave the bottom as it is where the top is fully transparent.
+        let transparent = fg_a.le(V::splat(0.));
+
+        let mut out = bg;
+        for (out, (cb, cs)) in out.iter_mut().zip(bg.into_iter().zip(fg)).take(3) {
+            // co = as * Cs' + ab * Cb * (1 - as)
+            // Cs' = (1 - ab) * Cs + ab * B(Cb, Cs)
+            let blended = K::blend(cb, cs, one);
+            let cs = one.sub(bg_a).mul(cs).add(bg_a.mul(blended));
+            let co = fg_a.mul(cs).add(bg_weight.mul(cb));
+            *out = V::select(transparent, cb, co.div_or_zero(alpha_final));
+        }
+        out[3] = V::select(transparent, bg_a, alpha_final);
+        out
+    }
+
+
+    /// f32 lanes holding one channel of consecutive RGBA pixels in [0, 1].
+    trait Lanes: Copy {
+
+        /// The number of bytes of RGBA8 pixels in the lanes.
+        const BYTES: usize;
+
+        /// Loads RGBA8 pixels and splits them into R, G, B and A lanes.
+        unsafe fn load(src: &[u8]) -> [Self; 4];
+
+        /// Stores R, G, B and A lanes as RGBA8 pixels.
+        unsafe fn store(channels: [Self; 4], dst: &mut [u8]);
+
+        unsafe fn splat(value: f32) -> Self;
+        unsafe fn add(self, rhs: Self) -> Self;
+        unsafe fn sub(self, rhs: Self) -> Self;
+        unsafe fn mul(self, rhs: Self) -> Self;
+        unsafe fn min(self, rhs: Self) -> Self;
+        unsafe fn max(self, rhs: Self) -> Self;
+
+        /// Returns 0 where `rhs` is not positive.
+        unsafe fn div_or_zero(self, rhs: Self) -> Self;
+
+        unsafe fn lt(self, rhs: Self) -> Self;
+        unsafe fn le(self, rhs: Self) -> Self;
+
+        /// Takes `a` where `mask` is set, otherwise `b`.
+        unsafe fn select(mask: Self, a: Self, b: Self) -> Self;
+    }
+
+    /// 4 pixels with SSE2.
+    #[derive(Clone, Copy)]
+    struct F32x4(__m128);
+
+    impl Lanes for F32x4 {
+        const BYTES: usize = 16;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u8]) -> [Self; 4] {
+            let v = _mm_loadu_si128(src[..16].as_ptr() as *const __m128i);
+            let byte = _mm_set1_epi32(0xFF);
+            let scale = _mm_set1_ps(1. / 255.);
+            let channel = |v| Self(_mm_mul_ps(_mm_cvtepi32_ps(v), scale));
+
+            [
+                channel(_mm_and_si128(v, byte)),
+                channel(_mm_and_si128(_mm_srli_epi32::<8>(v), byte)),
+                channel(_mm_and_si128(_mm_srli_epi32::<16>(v), byte)),
+                channel(_mm_srli_epi32::<24>(v)),
+            ]
+        }
+
+        #[inline(always)]
+        unsafe fn store([r, g, b, a]: [Self; 4], dst: &mut [u8]) {
+            // Rounds half away from zero as the scalar path does.
+            let (zero, max, half) = (_mm_setzero_ps(), _mm_set1_ps(255.), _mm_set1_ps(0.5));
+            let channel = |v: Self| {
+                let v = _mm_min_ps(_mm_max_ps(_mm_mul_ps(v.0, max), zero), max);
+                _mm_cvttps_epi32(_mm_add_ps(v, half))
+            };
+
+            let v = _mm_or_si128(
+                _mm_or_si128(channel(r), _mm_slli_epi32::<8>(channel(g))),
+                _mm_or_si128(_mm_slli_epi32::<16>(channel(b)), _mm_slli_epi32::<24>(channel(a))),
+            );
+            _mm_storeu_si128(dst[..16].as_mut_ptr() as *mut __m128i, v);
+        }
+
+        #[inline(always)]
+        unsafe fn splat(value: f32) -> Self {
+            Self(_mm_set1_ps(value))
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            Self(_mm_add_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn sub(self, rhs: Self) -> Self {
+            Self(_mm_sub_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            Self(_mm_mul_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn min(self, rhs: Self) -> Self {
+            Self(_mm_min_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn max(self, rhs: Self) -> Self {
+            Self(_mm_max_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn div_or_zero(self, rhs: Self) -> Self {
+            let positive = _mm_cmpgt_ps(rhs.0, _mm_setzero_ps());
+            Self(_mm_and_ps(_mm_div_ps(self.0, rhs.0), positive))
+        }
+
+        #[inline(always)]
+        unsafe fn lt(self, rhs: Self) -> Self {
+            Self(_mm_cmplt_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn le(self, rhs: Self) -> Self {
+            Self(_mm_cmple_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn select(mask: Self, a: Self, b: Self) -> Self {
+            Self(_mm_or_ps(_mm_and_ps(mask.0, a.0), _mm_andnot_ps(mask.0, b.0)))
+        }
+    }
+
+    /// 8 pixels with AVX2.
+    #[derive(Clone, Copy)]
+    struct F32x8(__m256);
+
+    impl Lanes for F32x8 {
+        const BYTES: usize = 32;
+
+        #[inline(always)]
+        unsafe fn load(src: &[u8]) -> [Self; 4] {
+            let v = _mm256_loadu_si256(src[..32].as_ptr() as *const __m256i);
+            let byte = _mm256_set1_epi32(0xFF);
+            let scale = _mm256_set1_ps(1. / 255.);
+            let channel = |v| Self(_mm256_mul_ps(_mm256_cvtepi32_ps(v), scale));
+
+            [
+                channel(_mm256_and_si256(v, byte)),
+                channel(_mm256_and_si256(_mm256_srli_epi32::<8>(v), byte)),
+                channel(_mm256_and_si256(_mm256_srli_epi32::<16>(v), byte)),
+                channel(_mm256_srli_epi32::<24>(v)),
+            ]
+        }
+
+        #[inline(always)]
+        unsafe fn store([r, g, b, a]: [Self; 4], dst: &mut [u8]) {
+            // Rounds half away from zero as the scalar path does.
+            let (zero, max, half) = (_mm256_setzero_ps(), _mm256_set1_ps(255.), _mm256_set1_ps(0.5));
+            let channel = |v: Self| {
+                let v = _mm256_min_ps(_mm256_max_ps(_mm256_mul_ps(v.0, max), zero), max);
+                _mm256_cvttps_epi32(_mm256_add_ps(v, half))
+            };
+
+            let v = _mm256_or_si256(
+                _mm256_or_si256(channel(r), _mm256_slli_epi32::<8>(channel(g))),
+                _mm256_or_si256(_mm256_slli_epi32::<16>(channel(b)), _mm256_slli_epi32::<24>(channel(a))),
+            );
+            _mm256_storeu_si256(dst[..32].as_mut_ptr() as *mut __m256i, v);
+        }
+
+        #[inline(always)]
+        unsafe fn splat(value: f32) -> Self {
+            Self(_mm256_set1_ps(value))
+        }
+
+        #[inline(always)]
+        unsafe fn add(self, rhs: Self) -> Self {
+            Self(_mm256_add_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn sub(self, rhs: Self) -> Self {
+            Self(_mm256_sub_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(self, rhs: Self) -> Self {
+            Self(_mm256_mul_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn min(self, rhs: Self) -> Self {
+            Self(_mm256_min_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn max(self, rhs: Self) -> Self {
+            Self(_mm256_max_ps(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn div_or_zero(self, rhs: Self) -> Self {
+            let positive = _mm256_cmp_ps::<_CMP_GT_OQ>(rhs.0, _mm256_setzero_ps());
+            Self(_mm256_and_ps(_mm256_div_ps(self.0, rhs.0), positive))
+        }
+
+        #[inline(always)]
+        unsafe fn lt(self, rhs: Self) -> Self {
+            Self(_mm256_cmp_ps::<_CMP_LT_OQ>(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn le(self, rhs: Self) -> Self {
+            Self(_mm256_cmp_ps::<_CMP_LE_OQ>(self.0, rhs.0))
+        }
+
+        #[inline(always)]
+        unsafe fn select(mask: Self, a: Self, b: Self) -> Self {
+            Self(_mm256_blendv_ps(b.0, a.0, mask.0))
+        }
+    }
+
+
+    /// B(Cb, Cs) of a separable blend mode. See `blend/color_per_channel.rs`.
+    trait Kernel {
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, one: V) -> V;
+    }
+
+    struct Normal;
+    struct Multiply;
+    struct Screen;
+    struct Overlay;
+    struct Darken;
+    struct Lighten;
+    struct Difference;
+    struct LinearDodge;
+    struct Subtract;
+
+    impl Kernel for Normal {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(_cb: V, cs: V, _one: V) -> V {
+            cs
+        }
+    }
+
+    impl Kernel for Multiply {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.mul(cs)
+        }
+    }
+
+    impl Kernel for Screen {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.add(cs).sub(cb.mul(cs))
+        }
+    }
+
+    impl Kernel for Overlay {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, one: V) -> V {
+            // hard_light(Cs, Cb)
+            let cb2 = cb.add(cb);
+            let multiply = cs.mul(cb2);
+            let cb2 = cb2.sub(one);
+            let screen = cs.add(cb2).sub(cs.mul(cb2));
+            V::select(cb.lt(V::splat(0.5)), multiply, screen)
+        }
+    }
+
+    impl Kernel for Darken {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.min(cs)
+        }
+    }
+
+    impl Kernel for Lighten {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.max(cs)
+        }
+    }
+
+    impl Kernel for Difference {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.sub(cs).max(cs.sub(cb))
+        }
+    }
+
+    impl Kernel for LinearDodge {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, one: V) -> V {
+            cb.add(cs).min(one)
+        }
+    }
+
+    impl Kernel for Subtract {
+        #[inline(always)]
+        unsafe fn blend<V: Lanes>(cb: V, cs: V, _one: V) -> V {
+            cb.sub(cs).max(V::splat(0.))
+        }
+    }
+}
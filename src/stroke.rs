@@ -0,0 +1,58 @@
+use crate::{as_rgba::Float, BlendMode, StrokePosition};
+
+
+/// An outline along the edge of the alpha of a layer.  
+/// 
+/// The edge is where the alpha crosses 0.5, and the outline is antialiased. See [`Effects`](crate::Effects).  
+/// 
+/// An outside stroke is drawn beneath the layer and also fills the area under it,
+/// so there is no seam along antialiased edges, and translucent parts of the layer show the stroke through them.
+/// Inside and center strokes are drawn above the layer.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+    /// Width in pixels. (0.0 <= value <= 250.0)  
+    /// Larger widths are capped at 250 pixels, like the size of Photoshop.
+    pub width: Float,
+
+    /// Straight RGB color. (0.0 <= value <= 1.0)
+    pub color: [Float; 3],
+
+    pub position: StrokePosition,
+
+    pub blend_mode: BlendMode,
+
+    /// Opacity of the stroke, multiplied by the opacity of the layer. (0.0 <= value <= 1.0)
+    pub opacity: Float,
+}
+
+impl Stroke {
+
+    /// Outside stroke blended with `BlendMode::Normal`.
+    pub const fn new(width: Float, color: [Float; 3]) -> Self {
+        Self {
+            width,
+            color,
+            position: StrokePosition::Outside,
+            blend_mode: BlendMode::Normal,
+            opacity: 1.,
+        }
+    }
+
+    pub const fn with_position(mut self, position: StrokePosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub const fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    pub const fn with_opacity(mut self, opacity: Float) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
@@ -0,0 +1,11 @@
+/// Where a [`Stroke`](crate::Stroke) is drawn relative to the edge of a layer.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokePosition {
+    #[default]
+    Outside,
+    Inside,
+    Center,
+}
@@ -0,0 +1,44 @@
+use crate::WrapMode;
+
+
+/// How [`overlay_tiled`](crate::overlay_tiled) repeats the top image.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+/// 
+/// # Usage
+/// ``````
+/// use image_overlay::{Tiling, WrapMode};
+/// 
+/// // Repeat only horizontally, starting from the center of the first tile.
+/// let tiling = Tiling::new(WrapMode::Repeat, WrapMode::Once).with_phase(16, 0);
+/// ``````
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tiling {
+    pub wrap_x: WrapMode,
+    pub wrap_y: WrapMode,
+
+    /// Coordinate of the pattern at the top-left corner of the filled rectangle.
+    pub phase_x: i64,
+
+    /// Coordinate of the pattern at the top-left corner of the filled rectangle.
+    pub phase_y: i64,
+}
+
+impl Tiling {
+
+    pub const fn new(wrap_x: WrapMode, wrap_y: WrapMode) -> Self {
+        Self {
+            wrap_x,
+            wrap_y,
+            phase_x: 0,
+            phase_y: 0,
+        }
+    }
+
+    pub const fn with_phase(mut self, phase_x: i64, phase_y: i64) -> Self {
+        self.phase_x = phase_x;
+        self.phase_y = phase_y;
+        self
+    }
+}
@@ -0,0 +1,49 @@
+/// How a tiled image repeats along an axis.  
+/// 
+/// If you need serde::Serialize/Deserialize, please enable "serde" feature.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WrapMode {
+    #[default]
+    Repeat,
+
+    /// Every other tile is flipped, so that neighboring tiles meet at the same pixels.
+    MirrorRepeat,
+
+    /// The image is not repeated along the axis.
+    Once,
+}
+
+impl WrapMode {
+
+    /// Maps a coordinate of the pattern to a coordinate of the image, or `None` if it is not covered.
+    #[inline(always)]
+    pub(crate) fn wrap(self, coordinate: i64, size: u32) -> Option<u32> {
+        let size = i64::from(size);
+        let coordinate = match self {
+            WrapMode::Repeat => coordinate.rem_euclid(size),
+            WrapMode::MirrorRepeat => {
+                let coordinate = coordinate.rem_euclid(size * 2);
+                if coordinate < size { coordinate } else { size * 2 - 1 - coordinate }
+            }
+            WrapMode::Once => {
+                if !(0..size).contains(&coordinate) {
+                    return None;
+                }
+                coordinate
+            }
+        };
+        Some(coordinate as u32)
+    }
+
+    /// Reduces a phase to the smallest one with the same result, so that adding a coordinate to it does not overflow.
+    #[inline(always)]
+    pub(crate) fn reduce(self, phase: i64, size: u32) -> i64 {
+        let size = i64::from(size);
+        match self {
+            WrapMode::Repeat => phase.rem_euclid(size),
+            WrapMode::MirrorRepeat => phase.rem_euclid(size * 2),
+            WrapMode::Once => phase,
+        }
+    }
+}
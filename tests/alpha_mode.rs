@@ -0,0 +1,79 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with_options, AlphaMode, BlendMode, CompositeOperator, OverlayOptions};
+
+const MODES: [BlendMode; 8] = [
+    BlendMode::Normal,
+    BlendMode::Multiply,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::SoftLight,
+    BlendMode::LinearDodge,
+    BlendMode::Hue,
+    BlendMode::Luminosity,
+];
+
+const OPERATORS: [CompositeOperator; 3] = [
+    CompositeOperator::SourceOver,
+    CompositeOperator::SourceAtop,
+    CompositeOperator::Xor,
+];
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r / a, g / a, b / a, a]
+}
+
+fn overlay_pixel(bottom: [f32; 4], top: [f32; 4], options: OverlayOptions) -> [f32; 4] {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba(bottom));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba(top));
+    overlay_with_options(&mut bottom, &top, 0, 0, options);
+    bottom.get_pixel(0, 0).0
+}
+
+fn assert_close(a: [f32; 4], b: [f32; 4], message: &str) {
+    for (a, b) in a.iter().zip(b) {
+        assert!((a - b).abs() < 1e-5, "{message}: {a} != {b}");
+    }
+}
+
+#[test]
+fn premultiplied_matches_straight() {
+    let bottom = [0.2, 0.6, 0.8, 0.5];
+    let top = [0.9, 0.3, 0.1, 0.6];
+
+    for mode in MODES {
+        for operator in OPERATORS {
+            for opacity in [1., 0.5] {
+                let options = OverlayOptions::new(mode)
+                    .with_composite_operator(operator)
+                    .with_opacity(opacity);
+
+                let straight = overlay_pixel(bottom, top, options);
+                let premultiplied = overlay_pixel(
+                    premultiply(bottom),
+                    premultiply(top),
+                    options.with_alpha_mode(AlphaMode::Premultiplied),
+                );
+
+                assert_close(unpremultiply(premultiplied), straight, &format!("{mode}, {operator}, {opacity}"));
+            }
+        }
+    }
+}
+
+#[test]
+fn opaque_pixels_are_the_same_in_both_modes() {
+    let bottom = [0.2, 0.6, 0.8, 1.];
+    let top = [0.9, 0.3, 0.1, 1.];
+
+    for mode in MODES {
+        let options = OverlayOptions::new(mode);
+        let straight = overlay_pixel(bottom, top, options);
+        let premultiplied = overlay_pixel(bottom, top, options.with_alpha_mode(AlphaMode::Premultiplied));
+
+        assert_close(premultiplied, straight, &mode.to_string());
+    }
+}
@@ -0,0 +1,54 @@
+use image::{Luma, LumaA, Rgb, Rgba};
+use image_overlay::AsRgba;
+
+
+fn assert_round_trip<P: AsRgba + PartialEq + std::fmt::Debug>(pixel: P) {
+    assert_eq!(P::from_rgba(pixel.to_rgba()), pixel);
+}
+
+macro_rules! assert_round_trip_all {
+    ($T: ty, $values: expr) => {
+        for v in $values {
+            let w: $T = !v;
+            assert_round_trip(Rgb([v, w, v]));
+            assert_round_trip(Rgba([v, w, v, w]));
+            assert_round_trip(Luma([v]));
+            assert_round_trip(LumaA([v, w]));
+        }
+    };
+}
+
+#[test]
+fn round_trip_8bit() {
+    assert_round_trip_all!(u8, u8::MIN..=u8::MAX);
+    assert_round_trip_all!(i8, i8::MIN..=i8::MAX);
+}
+
+#[test]
+fn round_trip_16bit() {
+    assert_round_trip_all!(u16, u16::MIN..=u16::MAX);
+    assert_round_trip_all!(i16, i16::MIN..=i16::MAX);
+}
+
+#[test]
+fn round_trip_wide_bounds() {
+    // Wider integers can not be represented exactly by the intermediate float.
+    assert_round_trip_all!(u32, [u32::MIN, u32::MAX]);
+    assert_round_trip_all!(u64, [u64::MIN, u64::MAX]);
+    assert_round_trip_all!(usize, [usize::MIN, usize::MAX]);
+    assert_round_trip_all!(i32, [i32::MIN, 0, i32::MAX]);
+    assert_round_trip_all!(i64, [i64::MIN, 0, i64::MAX]);
+    assert_round_trip_all!(isize, [isize::MIN, 0, isize::MAX]);
+}
+
+#[test]
+fn from_rgba_rounds_to_nearest() {
+    assert_eq!(Rgba::<u8>::from_rgba([0.5, 0.499, 1. / 255. * 0.6, 1.]), Rgba([128, 127, 1, 255]));
+    assert_eq!(Luma::<u16>::from_rgba([0.5, 0.5, 0.5, 1.]), Luma([32768]));
+}
+
+#[test]
+fn from_rgba_clamps() {
+    assert_eq!(Rgba::<u8>::from_rgba([1.5, -0.5, 2., -1.]), Rgba([255, 0, 255, 0]));
+    assert_eq!(Rgb::<u16>::from_rgba([1.5, -0.5, 1., 1.]), Rgb([65535, 0, 65535]));
+}
@@ -0,0 +1,79 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with_options, BlendIf, BlendIfChannel, BlendIfRange, BlendMode, OverlayOptions};
+
+fn overlay_pixel(bottom: [f32; 4], top: [f32; 4], options: OverlayOptions) -> [f32; 4] {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba(bottom));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba(top));
+    overlay_with_options(&mut bottom, &top, 0, 0, options);
+    bottom.get_pixel(0, 0).0
+}
+
+fn gray(value: f32) -> [f32; 4] {
+    [value, value, value, 1.]
+}
+
+/// Draws the top pixel onto black and returns the red channel, which is the top value scaled by the coverage.
+fn this_layer(channel: BlendIfChannel, range: BlendIfRange, top: [f32; 4]) -> f32 {
+    let blend_if = BlendIf::new(channel).with_this_layer(range);
+    overlay_pixel(gray(0.), top, OverlayOptions::new(BlendMode::Normal).with_blend_if(blend_if))[0]
+}
+
+/// Draws white onto the bottom pixel and returns the red channel.
+fn underlying_layer(range: BlendIfRange, bottom: [f32; 4]) -> f32 {
+    let blend_if = BlendIf::new(BlendIfChannel::Gray).with_underlying_layer(range);
+    overlay_pixel(bottom, gray(1.), OverlayOptions::new(BlendMode::Normal).with_blend_if(blend_if))[0]
+}
+
+fn assert_close(a: f32, b: f32, message: &str) {
+    assert!((a - b).abs() < 1e-6, "{message}: {a} != {b}");
+}
+
+#[test]
+fn hard_range() {
+    // Both ends are included.
+    let range = BlendIfRange::new(0.25, 0.75);
+    for (value, expected) in [(0.24, 0.), (0.25, 0.25), (0.5, 0.5), (0.75, 0.75), (0.76, 0.)] {
+        assert_close(this_layer(BlendIfChannel::Red, range, gray(value)), expected, &value.to_string());
+    }
+}
+
+#[test]
+fn soft_ramps() {
+    // Halfway up the black ramp and halfway down the white ramp.
+    let range = BlendIfRange::FULL.with_black_split(0.2, 0.4).with_white_split(0.6, 1.);
+    assert_close(this_layer(BlendIfChannel::Gray, range, gray(0.3)), 0.15, "black");
+    assert_close(this_layer(BlendIfChannel::Gray, range, gray(0.8)), 0.4, "white");
+    assert_close(this_layer(BlendIfChannel::Gray, range, gray(0.5)), 0.5, "between");
+    assert_close(this_layer(BlendIfChannel::Gray, range, gray(0.1)), 0., "below");
+}
+
+#[test]
+fn underlying_range() {
+    let range = BlendIfRange::FULL.with_white_split(0.5, 0.7);
+    assert_close(underlying_layer(range, gray(0.4)), 1., "shown");
+    assert_close(underlying_layer(range, gray(0.6)), 0.8, "midpoint");
+    assert_close(underlying_layer(range, gray(0.8)), 0.8, "hidden");
+}
+
+#[test]
+fn channels() {
+    let range = BlendIfRange::new(0.5, 1.);
+    let top = [0.6, 0.2, 0.4, 1.];
+
+    assert_close(this_layer(BlendIfChannel::Red, range, top), 0.6, "red");
+    assert_close(this_layer(BlendIfChannel::Green, range, top), 0., "green");
+    assert_close(this_layer(BlendIfChannel::Blue, range, top), 0., "blue");
+
+    // 0.3 * 0.6 + 0.59 * 0.2 + 0.11 * 0.4 = 0.342
+    assert_close(this_layer(BlendIfChannel::Gray, range, top), 0., "gray");
+    assert_close(this_layer(BlendIfChannel::Gray, BlendIfRange::new(0.34, 1.), top), 0.6, "gray");
+}
+
+#[test]
+fn coverage_scales_opacity() {
+    let blend_if = BlendIf::new(BlendIfChannel::Gray).with_this_layer(BlendIfRange::FULL.with_black_split(0., 1.));
+    let options = OverlayOptions::new(BlendMode::Screen).with_opacity(0.5).with_blend_if(blend_if);
+
+    // Screen gives 0.75, and the coverage of 0.5 and the opacity of 0.5 take a quarter of the way from 0.5.
+    assert_close(overlay_pixel(gray(0.5), gray(0.5), options)[0], 0.5625, "screen");
+}
@@ -0,0 +1,118 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay, BlendMode, BlendModeCategory, Float};
+
+
+type Rgb = [Float; 3];
+
+const EPSILON: Float = 1e-4;
+
+/// Grays and some saturated colors, including two with the same luminance.
+fn colors() -> Vec<Rgb> {
+    let mut colors: Vec<Rgb> = [0., 0.1, 0.25, 0.5, 0.75, 0.9, 1.].map(|v| [v, v, v]).to_vec();
+    colors.extend([
+        [1., 0., 0.],
+        [0., 0.8, 0.2],
+        [0.1, 0.3, 0.9],
+        [0.9, 0.6, 0.1],
+        [0.4, 0.1, 0.6],
+        [0.2, 0.7, 0.7],
+        [0.59, 0., 0.],
+        [0., 0.3, 0.],
+    ]);
+    colors
+}
+
+/// Blends opaque colors, where the result is the blend mode itself.
+fn blend(mode: BlendMode, bottom: Rgb, top: Rgb) -> Rgb {
+    let [r, g, b] = bottom;
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([r, g, b, 1.]));
+    let [r, g, b] = top;
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([r, g, b, 1.]));
+    overlay(&mut bottom, &top, 0, 0, mode);
+
+    let [r, g, b, _] = bottom.get_pixel(0, 0).0;
+    [r, g, b]
+}
+
+fn approx_eq(a: Rgb, b: Rgb) -> bool {
+    a.iter().zip(b).all(|(a, b)| (a - b).abs() < EPSILON)
+}
+
+fn lum([r, g, b]: Rgb) -> Float {
+    0.3 * r + 0.59 * g + 0.11 * b
+}
+
+#[test]
+fn neutral_color() {
+    for mode in BlendMode::iter() {
+        match mode.neutral_color() {
+            Some(neutral) => {
+                for bottom in colors() {
+                    let result = blend(mode, bottom, neutral);
+                    assert!(approx_eq(result, bottom), "{mode}: {bottom:?} -> {result:?}");
+                }
+            }
+            None => {
+                // No gray leaves every color unchanged.
+                for v in [0., 0.25, 0.5, 0.75, 1.] {
+                    let changed = colors().into_iter().any(|bottom| !approx_eq(blend(mode, bottom, [v, v, v]), bottom));
+                    assert!(changed, "{mode}: {v} is neutral");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn separable() {
+    for mode in BlendMode::iter() {
+        let mut separable = true;
+        for bottom in colors() {
+            for top in colors() {
+                let result = blend(mode, bottom, top);
+                for c in 0..3 {
+                    let gray = blend(mode, [bottom[c]; 3], [top[c]; 3]);
+                    separable &= (result[c] - gray[c]).abs() < EPSILON;
+                }
+            }
+        }
+        assert_eq!(mode.is_separable(), separable, "{mode}");
+    }
+}
+
+#[test]
+fn commutative() {
+    for mode in BlendMode::iter() {
+        let mut commutative = true;
+        for bottom in colors() {
+            for top in colors() {
+                commutative &= approx_eq(blend(mode, bottom, top), blend(mode, top, bottom));
+            }
+        }
+        assert_eq!(mode.is_commutative(), commutative, "{mode}");
+    }
+}
+
+#[test]
+fn category() {
+    for mode in BlendMode::iter() {
+        for bottom in colors() {
+            for top in colors() {
+                let result = blend(mode, bottom, top);
+                match mode.category() {
+                    BlendModeCategory::Darken => assert!(lum(result) <= lum(bottom) + EPSILON, "{mode}"),
+                    BlendModeCategory::Lighten => assert!(lum(result) >= lum(bottom) - EPSILON, "{mode}"),
+                    _ => {}
+                }
+            }
+        }
+
+        if mode.category() == BlendModeCategory::Component {
+            assert!(!mode.is_separable(), "{mode}");
+        }
+    }
+
+    assert_eq!(BlendMode::Normal.category(), BlendModeCategory::Normal);
+    assert_eq!(BlendMode::Overlay.category(), BlendModeCategory::Contrast);
+    assert_eq!(BlendMode::Difference.category(), BlendModeCategory::Inversion);
+}
@@ -0,0 +1,223 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{blend, overlay, overlay_with_options, BlendMode, OverlayOptions, ParseBlendModeError};
+
+type BlendFn = fn(&mut Rgba<f32>, &Rgba<f32>);
+
+const VALUES: [f32; 7] = [0., 0.1, 0.25, 0.5, 0.6, 0.9, 1.];
+
+/// Blends opaque pixels, where the result is the blend function itself.
+fn assert_formula(blend_fn: BlendFn, formula: fn(f32, f32) -> f32) {
+    for base in VALUES {
+        for top in VALUES {
+            let mut bg = Rgba([base, base, base, 1.]);
+            blend_fn(&mut bg, &Rgba([top, top, top, 1.]));
+
+            let expected = formula(base, top);
+            assert!((bg[0] - expected).abs() < 1e-6, "base: {base}, top: {top}, result: {}, expected: {expected}", bg[0]);
+            assert_eq!(bg[3], 1.);
+        }
+    }
+}
+
+#[test]
+fn add() {
+    assert_formula(blend::blend_add, |b, s| b + s);
+}
+
+#[test]
+fn average() {
+    assert_formula(blend::blend_average, |b, s| (b + s) / 2.);
+}
+
+#[test]
+fn negation() {
+    assert_formula(blend::blend_negation, |b, s| 1. - (1. - b - s).abs());
+}
+
+#[test]
+fn reflect() {
+    assert_formula(blend::blend_reflect, |b, s| if s == 1. { 1. } else { (b * b / (1. - s)).min(1.) });
+}
+
+#[test]
+fn glow() {
+    assert_formula(blend::blend_glow, |b, s| if b == 1. { 1. } else { (s * s / (1. - b)).min(1.) });
+}
+
+#[test]
+fn freeze() {
+    assert_formula(blend::blend_freeze, |b, s| {
+        if b == 1. { 1. } else if s == 0. { 0. } else { (1. - (1. - b).powi(2) / s).max(0.) }
+    });
+}
+
+#[test]
+fn heat() {
+    assert_formula(blend::blend_heat, |b, s| {
+        if s == 1. { 1. } else if b == 0. { 0. } else { (1. - (1. - s).powi(2) / b).max(0.) }
+    });
+}
+
+#[test]
+fn phoenix() {
+    assert_formula(blend::blend_phoenix, |b, s| b.min(s) - b.max(s) + 1.);
+}
+
+#[test]
+fn grain_extract() {
+    assert_formula(blend::blend_grain_extract, |b, s| (b - s + 0.5).clamp(0., 1.));
+}
+
+#[test]
+fn grain_merge() {
+    assert_formula(blend::blend_grain_merge, |b, s| (b + s - 0.5).clamp(0., 1.));
+}
+
+#[test]
+fn lch_modes_keep_identical_colors() {
+    let color = Rgba([0.8f32, 0.3, 0.1, 1.]);
+    for blend_fn in [blend::blend_lch_hue, blend::blend_lch_chroma, blend::blend_lch_color, blend::blend_lch_lightness] as [BlendFn; 4] {
+        let mut bg = color;
+        blend_fn(&mut bg, &color);
+        for c in 0..3 {
+            assert!((bg[c] - color[c]).abs() < 1e-4, "{bg:?}");
+        }
+    }
+}
+
+#[test]
+fn lch_modes_on_grays() {
+    let color = Rgba([0.8f32, 0.3, 0.1, 1.]);
+    for v in [0., 0.5, 1.] {
+        let gray = Rgba([v, v, v, 1.]);
+
+        // Grays have no hue to keep or chroma to scale.
+        for blend_fn in [blend::blend_lch_hue, blend::blend_lch_chroma] as [BlendFn; 2] {
+            let mut bg = gray;
+            blend_fn(&mut bg, &color);
+            for c in 0..3 {
+                assert!((bg[c] - v).abs() < 1e-4, "{bg:?}");
+            }
+        }
+
+        // A gray top takes the chroma away.
+        let mut bg = color;
+        blend::blend_lch_color(&mut bg, &gray);
+        assert!((bg[0] - bg[1]).abs() < 1e-4 && (bg[1] - bg[2]).abs() < 1e-4, "{bg:?}");
+    }
+}
+
+#[test]
+fn add_is_unclamped_for_float() {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([0.75f32, 0.5, 0.25, 1.]));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([0.5f32, 0.5, 0.5, 1.]));
+    overlay(&mut bottom, &top, 0, 0, BlendMode::Add);
+    assert_eq!(bottom.get_pixel(0, 0), &Rgba([1.25, 1., 0.75, 1.]));
+
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([0.75f32, 0.5, 0.25, 1.]));
+    overlay(&mut bottom, &top, 0, 0, BlendMode::LinearDodge);
+    assert_eq!(bottom.get_pixel(0, 0), &Rgba([1., 1., 0.75, 1.]));
+}
+
+#[test]
+fn add_is_clamped_for_integer() {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([200u8, 100, 0, 255]));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([100u8, 100, 100, 255]));
+    overlay(&mut bottom, &top, 0, 0, BlendMode::Add);
+    assert_eq!(bottom.get_pixel(0, 0), &Rgba([255, 200, 100, 255]));
+}
+
+#[test]
+fn hdr_keeps_highlights_for_float() {
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([1.5f32, 0.5, 0.5, 1.]));
+    let blend = |mode: BlendMode| {
+        let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([2.0f32, 0.5, 0.25, 1.]));
+        overlay_with_options(&mut bottom, &top, 0, 0, OverlayOptions::new(mode).with_hdr(true));
+        bottom.get_pixel(0, 0).0
+    };
+
+    let expected = [
+        (BlendMode::Normal, [1.5, 0.5, 0.5]),
+        (BlendMode::Darken, [1.5, 0.5, 0.25]),
+        (BlendMode::Multiply, [3., 0.25, 0.125]),
+        (BlendMode::Lighten, [2., 0.5, 0.5]),
+        (BlendMode::LinearDodge, [3.5, 1., 0.75]),
+        (BlendMode::Add, [3.5, 1., 0.75]),
+        (BlendMode::Average, [1.75, 0.5, 0.375]),
+        (BlendMode::Difference, [0.5, 0., 0.25]),
+        (BlendMode::Subtract, [0.5, 0., 0.]),
+        (BlendMode::Divide, [2. / 1.5, 1., 0.5]),
+        (BlendMode::GrainExtract, [1., 0.5, 0.25]),
+        (BlendMode::GrainMerge, [3., 0.5, 0.25]),
+
+        // Without HDR formulas, the inputs are clamped to 1.0: (1.0, 0.5, 0.25) and (1.0, 0.5, 0.5).
+        (BlendMode::Screen, [1., 0.75, 0.625]),
+        (BlendMode::ColorDodge, [1., 1., 0.5]),
+        (BlendMode::Exclusion, [0., 0.5, 0.5]),
+    ];
+    for (mode, [r, g, b]) in expected {
+        let result = blend(mode);
+        for (c, e) in result.iter().zip([r, g, b, 1.]) {
+            assert!((c - e).abs() < 1e-6, "{mode}: {result:?}, expected: {:?}", [r, g, b]);
+        }
+    }
+
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba([0.5f32, 0.5, 0.5, 1.]));
+    let bright = ImageBuffer::from_pixel(1, 1, Rgba([4.0f32, 4., 4., 1.]));
+    overlay_with_options(&mut bottom, &bright, 0, 0, OverlayOptions::new(BlendMode::Luminosity).with_hdr(true));
+    assert!(bottom.get_pixel(0, 0).0[..3].iter().all(|c| (c - 4.).abs() < 1e-5));
+}
+
+#[test]
+fn dispatch_matches_blend_functions() {
+    let modes: [(BlendMode, BlendFn); 8] = [
+        (BlendMode::Add, blend::blend_add),
+        (BlendMode::Average, blend::blend_average),
+        (BlendMode::Negation, blend::blend_negation),
+        (BlendMode::Reflect, blend::blend_reflect),
+        (BlendMode::Glow, blend::blend_glow),
+        (BlendMode::Freeze, blend::blend_freeze),
+        (BlendMode::Heat, blend::blend_heat),
+        (BlendMode::Phoenix, blend::blend_phoenix),
+    ];
+    let bg = Rgba([0.2f32, 0.5, 0.9, 0.7]);
+    let fg = Rgba([0.6f32, 0.3, 0.8, 0.4]);
+
+    for (mode, blend_fn) in modes {
+        let mut bottom = ImageBuffer::from_pixel(1, 1, bg);
+        overlay(&mut bottom, &ImageBuffer::from_pixel(1, 1, fg), 0, 0, mode);
+
+        let mut expected = bg;
+        blend_fn(&mut expected, &fg);
+        assert_eq!(bottom.get_pixel(0, 0), &expected, "{mode}");
+    }
+}
+
+#[test]
+fn parse_unknown_name() {
+    let error: ParseBlendModeError = "Glow Dodge".parse::<BlendMode>().unwrap_err();
+    assert_eq!(error.input(), "Glow Dodge");
+    assert_eq!(error.to_string(), r#"unknown blend mode: "Glow Dodge""#);
+
+    assert!("".parse::<BlendMode>().is_err());
+    assert!("normal mode".parse::<BlendMode>().is_err());
+}
+
+#[test]
+fn parse_add_aliases() {
+    // "add" has been an alias of LinearDodge before Add was introduced.
+    assert_eq!("add".parse(), Ok(BlendMode::LinearDodge));
+    assert_eq!("Add".parse(), Ok(BlendMode::LinearDodge));
+    assert_eq!("addition".parse(), Ok(BlendMode::Add));
+    assert_eq!("add_unclamped".parse(), Ok(BlendMode::Add));
+    assert_eq!(BlendMode::Add.to_string(), "Addition");
+}
+
+#[test]
+fn parse_new_modes() {
+    assert_eq!("Linear Dodge".parse(), Ok(BlendMode::LinearDodge));
+    assert_eq!("phoenix".parse(), Ok(BlendMode::Phoenix));
+    assert_eq!(BlendMode::Negation.to_string(), "Negation");
+    assert_eq!("grain-merge".parse(), Ok(BlendMode::GrainMerge));
+    assert_eq!("LCh Lightness".parse(), Ok(BlendMode::LchLightness));
+}
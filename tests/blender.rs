@@ -0,0 +1,95 @@
+use std::cell::Cell;
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with, overlay_with_options, AlphaMode, BlendMode, Blender, CompositeOperator, Float, OverlayOptions, PerChannel};
+
+type Rgb = (Float, Float, Float);
+
+fn overlay_pixel<L: Blender>(bottom: [f32; 4], top: [f32; 4], blender: &L, options: OverlayOptions) -> [f32; 4] {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba(bottom));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba(top));
+    overlay_with(&mut bottom, &top, 0, 0, blender, options);
+    bottom.get_pixel(0, 0).0
+}
+
+fn assert_close(a: [f32; 4], b: [f32; 4], message: &str) {
+    for (a, b) in a.iter().zip(b) {
+        assert!((a - b).abs() < 1e-6, "{message}: {a} != {b}");
+    }
+}
+
+#[test]
+fn closure_gives_its_formula_on_opaque_pixels() {
+    let blender = |cb: Rgb, cs: Rgb| (cb.0 * 0.5 + cs.0 * 0.25, cs.1, cb.2);
+    let out = overlay_pixel([0.5, 0.25, 1., 1.], [1., 0.5, 0.75, 1.], &blender, OverlayOptions::default());
+    assert_eq!(out, [0.5, 0.5, 1., 1.]);
+}
+
+#[test]
+fn per_channel_with_alpha_and_opacity() {
+    let average = PerChannel(|b: Float, s: Float| (b + s) / 2.);
+    let options = OverlayOptions::default().with_opacity(0.5);
+    let out = overlay_pixel([0.5, 0.25, 1., 0.5], [1., 0.5, 0., 0.5], &average, options);
+
+    // as = 0.5 * 0.5 and ab = 0.5, B = (0.75, 0.375, 0.5)
+    // Cs' = (1 - ab) * Cs + ab * B = (0.875, 0.4375, 0.25)
+    // co = as * Cs' + ab * (1 - as) * Cb = (0.40625, 0.203125, 0.4375)
+    // ao = as + ab * (1 - as) = 0.625
+    assert_close(out, [0.65, 0.325, 0.7, 0.625], "average");
+}
+
+#[test]
+fn per_channel_matches_built_in_mode() {
+    let multiply = PerChannel(|b: Float, s: Float| b * s);
+    let (bottom, top) = ([0.5, 0.25, 1., 0.5], [1., 0.5, 0.25, 0.75]);
+
+    for operator in [CompositeOperator::SourceOver, CompositeOperator::SourceAtop, CompositeOperator::Xor] {
+        let options = OverlayOptions::new(BlendMode::Multiply)
+            .with_composite_operator(operator)
+            .with_opacity(0.5);
+
+        let mut expected = ImageBuffer::from_pixel(1, 1, Rgba(bottom));
+        overlay_with_options(&mut expected, &ImageBuffer::from_pixel(1, 1, Rgba(top)), 0, 0, options);
+
+        assert_close(overlay_pixel(bottom, top, &multiply, options), expected.get_pixel(0, 0).0, &operator.to_string());
+    }
+}
+
+#[test]
+fn blender_takes_straight_colors() {
+    let inputs = Cell::new(((0., 0., 0.), (0., 0., 0.)));
+    let blender = |cb: Rgb, cs: Rgb| {
+        inputs.set((cb, cs));
+        cs
+    };
+
+    let options = OverlayOptions::default().with_alpha_mode(AlphaMode::Premultiplied);
+    overlay_pixel([0.25, 0.125, 0.5, 0.5], [0.5, 0.25, 0., 0.5], &blender, options);
+    assert_eq!(inputs.get(), ((0.5, 0.25, 1.), (1., 0.5, 0.)));
+}
+
+#[test]
+fn hdr_colors_are_passed_as_they_are() {
+    let inputs = Cell::new(((0., 0., 0.), (0., 0., 0.)));
+    let blender = |cb: Rgb, cs: Rgb| {
+        inputs.set((cb, cs));
+        (cb.0 + cs.0, cb.1 + cs.1, cb.2 + cs.2)
+    };
+
+    let options = OverlayOptions::default().with_hdr(true);
+    let out = overlay_pixel([2., 0.5, 0., 1.], [4., 0.25, 1., 1.], &blender, options);
+    assert_eq!(inputs.get(), ((2., 0.5, 0.), (4., 0.25, 1.)));
+    assert_eq!(out, [6., 0.75, 1., 1.]);
+}
+
+#[test]
+fn transparent_top_is_skipped() {
+    let called = Cell::new(false);
+    let blender = |_: Rgb, cs: Rgb| {
+        called.set(true);
+        cs
+    };
+
+    let bottom = [0.5, 0.25, 1., 0.5];
+    assert_eq!(overlay_pixel(bottom, [1., 1., 1., 0.], &blender, OverlayOptions::default()), bottom);
+    assert!(!called.get());
+}
@@ -0,0 +1,80 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with_options, BlendMode, ChannelMask, CompositeOperator, OverlayOptions};
+
+const BOTTOM: [f32; 4] = [0.5, 0.25, 1., 0.75];
+const TOP: [f32; 4] = [0.25, 0.5, 0.75, 0.5];
+
+const MODES: [BlendMode; 6] = [
+    BlendMode::Multiply,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Difference,
+    BlendMode::Hue,
+    BlendMode::Luminosity,
+];
+
+const OPERATORS: [CompositeOperator; 6] = [
+    CompositeOperator::SourceOver,
+    CompositeOperator::SourceIn,
+    CompositeOperator::DestinationOut,
+    CompositeOperator::Xor,
+    CompositeOperator::Copy,
+    CompositeOperator::Clear,
+];
+
+const MASKS: [ChannelMask; 4] = [
+    ChannelMask::RED,
+    ChannelMask::RGB,
+    ChannelMask::ALPHA,
+    ChannelMask::new(false, true, false, true),
+];
+
+fn overlay_pixel(bottom: [f32; 4], top: [f32; 4], options: OverlayOptions) -> [f32; 4] {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba(bottom));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba(top));
+    overlay_with_options(&mut bottom, &top, 0, 0, options);
+    bottom.get_pixel(0, 0).0
+}
+
+fn enabled(mask: ChannelMask) -> [bool; 4] {
+    [mask.red, mask.green, mask.blue, mask.alpha]
+}
+
+#[test]
+fn masked_channels_keep_bottom() {
+    for mode in MODES {
+        for operator in OPERATORS {
+            for mask in MASKS {
+                let options = OverlayOptions::new(mode)
+                    .with_composite_operator(operator)
+                    .with_opacity(0.75);
+
+                let full = overlay_pixel(BOTTOM, TOP, options);
+                let masked = overlay_pixel(BOTTOM, TOP, options.with_channel_mask(mask));
+
+                for (i, enabled) in enabled(mask).into_iter().enumerate() {
+                    let expected = if enabled { full[i] } else { BOTTOM[i] };
+                    assert_eq!(masked[i], expected, "{mode}, {operator}, {mask:?}, channel {i}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn multiply_red_only() {
+    let options = OverlayOptions::new(BlendMode::Multiply).with_channel_mask(ChannelMask::RED);
+    let out = overlay_pixel([0.5, 0.25, 1., 1.], [0.5, 0.5, 0.5, 1.], options);
+    assert_eq!(out, [0.25, 0.25, 1., 1.]);
+}
+
+#[test]
+fn clear_keeps_masked_alpha() {
+    let options = OverlayOptions::new(BlendMode::Normal)
+        .with_composite_operator(CompositeOperator::Clear)
+        .with_channel_mask(ChannelMask::RGB);
+    assert_eq!(overlay_pixel(BOTTOM, TOP, options), [0., 0., 0., 0.75]);
+
+    let options = options.with_channel_mask(ChannelMask::ALPHA);
+    assert_eq!(overlay_pixel(BOTTOM, TOP, options), [0.5, 0.25, 1., 0.]);
+}
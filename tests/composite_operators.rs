@@ -0,0 +1,66 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with_options, BlendMode, CompositeOperator, OverlayOptions};
+
+const BOTTOM: [f32; 4] = [0.5, 0.25, 1., 0.25];
+const TOP: [f32; 4] = [1., 0.5, 0., 0.75];
+
+fn composite(options: OverlayOptions) -> [f32; 4] {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, Rgba(BOTTOM));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba(TOP));
+    overlay_with_options(&mut bottom, &top, 0, 0, options);
+    bottom.get_pixel(0, 0).0
+}
+
+fn assert_close(a: [f32; 4], b: [f32; 4], message: &str) {
+    for (a, b) in a.iter().zip(b) {
+        assert!((a - b).abs() < 1e-6, "{message}: {a} != {b}");
+    }
+}
+
+#[test]
+fn operators_on_translucent_pixels() {
+    let cases = [
+        (CompositeOperator::SourceIn, [1., 0.5, 0., 3. / 16.]),
+        (CompositeOperator::SourceOut, [1., 0.5, 0., 9. / 16.]),
+        (CompositeOperator::DestinationIn, [0.5, 0.25, 1., 3. / 16.]),
+        (CompositeOperator::DestinationOut, [0.5, 0.25, 1., 1. / 16.]),
+        (CompositeOperator::SourceAtop, [7. / 8., 7. / 16., 1. / 4., 1. / 4.]),
+        (CompositeOperator::DestinationAtop, [7. / 8., 7. / 16., 1. / 4., 3. / 4.]),
+        (CompositeOperator::Xor, [19. / 20., 19. / 40., 1. / 10., 5. / 8.]),
+    ];
+
+    for (operator, expected) in cases {
+        let options = OverlayOptions::new(BlendMode::Normal).with_composite_operator(operator);
+        assert_close(composite(options), expected, &operator.to_string());
+    }
+}
+
+#[test]
+fn operators_with_blend_mode() {
+    // The blend mode applies where both pixels are present.
+    let cases = [
+        (CompositeOperator::SourceAtop, [25. / 32., 47. / 128., 1. / 4., 1. / 4.]),
+        (CompositeOperator::Xor, [67. / 80., 25. / 64., 1. / 10., 5. / 8.]),
+    ];
+
+    for (operator, expected) in cases {
+        let options = OverlayOptions::new(BlendMode::Multiply).with_composite_operator(operator);
+        assert_close(composite(options), expected, &operator.to_string());
+    }
+}
+
+#[test]
+fn operators_with_opacity() {
+    // The opacity interpolates the premultiplied colors between the bottom and the result.
+    let cases = [
+        (CompositeOperator::SourceIn, [5. / 7., 5. / 14., 4. / 7., 7. / 32.]),
+        (CompositeOperator::DestinationOut, [0.5, 0.25, 1., 5. / 32.]),
+    ];
+
+    for (operator, expected) in cases {
+        let options = OverlayOptions::new(BlendMode::Normal)
+            .with_composite_operator(operator)
+            .with_opacity(0.5);
+        assert_close(composite(options), expected, &operator.to_string());
+    }
+}
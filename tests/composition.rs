@@ -0,0 +1,92 @@
+use image::{DynamicImage, ImageBuffer, Rgba, Rgba32FImage};
+use image_overlay::{BlendMode, Composition, GroupBlending, Layer, LayerGroup};
+
+fn image(width: u32, color: [f32; 4]) -> DynamicImage {
+    DynamicImage::ImageRgba32F(ImageBuffer::from_pixel(width, 1, Rgba(color)))
+}
+
+/// Red at 0, green at 1 and blue over both, at half opacity.
+fn composition() -> Composition<'static> {
+    let mut composition = Composition::new(3, 1).with_background(Rgba([0., 0., 0., 1.]));
+    composition.push(Layer::new(image(1, [1., 0., 0., 1.])));
+    composition.push(Layer::new(image(1, [0., 1., 0., 1.])).with_offset(1, 0));
+    composition.push(Layer::new(image(2, [0., 0., 1., 1.])).with_opacity(0.5));
+    composition
+}
+
+fn pixels(image: &Rgba32FImage) -> Vec<[f32; 4]> {
+    image.pixels().map(|pixel| pixel.0).collect()
+}
+
+#[test]
+fn flatten() {
+    assert_eq!(pixels(&composition().flatten()), [
+        [0.5, 0., 0.5, 1.],
+        [0., 0.5, 0.5, 1.],
+        [0., 0., 0., 1.],
+    ]);
+}
+
+#[test]
+fn empty_composition_is_background() {
+    let composition = Composition::new(2, 1).with_background(Rgba([0.25, 0.5, 1., 0.5]));
+    assert!(composition.is_empty());
+    assert_eq!(pixels(&composition.flatten()), [[0.25, 0.5, 1., 0.5]; 2]);
+}
+
+#[test]
+fn hidden_layers_are_skipped() {
+    let mut composition = composition();
+    assert!(!composition.toggle(2));
+    assert_eq!(pixels(&composition.flatten()), [
+        [1., 0., 0., 1.],
+        [0., 1., 0., 1.],
+        [0., 0., 0., 1.],
+    ]);
+
+    assert!(composition.toggle(2));
+    composition.layer_mut(0).unwrap().visible = false;
+    assert_eq!(composition.flatten().get_pixel(0, 0).0, [0., 0., 0.5, 1.]);
+}
+
+#[test]
+fn reordering() {
+    // The blue layer moves to the bottom, so red and green cover it.
+    let mut composition = composition();
+    composition.move_layer(2, 0);
+    assert_eq!(pixels(&composition.flatten())[..2], [[1., 0., 0., 1.], [0., 1., 0., 1.]]);
+
+    // Green below blue.
+    composition.swap(0, 2);
+    assert_eq!(composition.flatten().get_pixel(1, 0).0, [0., 0.5, 0.5, 1.]);
+}
+
+#[test]
+fn insert_and_remove() {
+    let mut composition = composition();
+    composition.insert(3, Layer::new(image(3, [1., 1., 1., 1.])).with_blend_mode(BlendMode::Multiply));
+    assert_eq!(composition.len(), 4);
+    assert_eq!(composition.flatten().get_pixel(0, 0).0, [0.5, 0., 0.5, 1.]);
+
+    composition.remove(0);
+    assert_eq!(composition.flatten().get_pixel(0, 0).0, [0., 0., 0.5, 1.]);
+}
+
+#[test]
+fn flatten_into_ignores_background() {
+    let mut canvas = Rgba32FImage::from_pixel(3, 1, Rgba([1., 1., 1., 1.]));
+    composition().flatten_into(&mut canvas);
+    assert_eq!(pixels(&canvas)[2], [1., 1., 1., 1.]);
+    assert_eq!(pixels(&canvas)[1], [0., 0.5, 0.5, 1.]);
+}
+
+#[test]
+fn huge_offsets_saturate() {
+    let mut composition = Composition::new(2, 1).with_background(Rgba([0., 0., 0., 1.]));
+    let group = LayerGroup::new(GroupBlending::PassThrough).with_layers(vec![
+        Layer::new(image(1, [1., 0., 0., 1.])).with_offset(i64::MAX, i64::MIN),
+    ]);
+    composition.push(Layer::group(group).with_offset(i64::MAX, i64::MIN));
+
+    assert_eq!(pixels(&composition.flatten()), [[0., 0., 0., 1.]; 2]);
+}
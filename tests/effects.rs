@@ -0,0 +1,199 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay_with_effects, Float, BlendIf, BlendIfChannel, BlendIfRange, BlendMode, ChannelMask, CompositeOperator, Effects, OverlayOptions, Shadow, Stroke, StrokePosition};
+
+type Image = ImageBuffer<Rgba<f32>, Vec<f32>>;
+
+/// A white square from (15, 15) to (25, 25) whose border pixels are half transparent.
+fn square() -> Image {
+    ImageBuffer::from_fn(40, 40, |x, y| {
+        let alpha = match (x, y) {
+            (15..=24, 15..=24) => 1.,
+            (14..=25, 14..=25) => 0.5,
+            _ => 0.,
+        };
+        Rgba([1., 1., 1., alpha])
+    })
+}
+
+fn black() -> Image {
+    ImageBuffer::from_pixel(40, 40, Rgba([0., 0., 0., 1.]))
+}
+
+#[test]
+fn outside_stroke_has_no_seam() {
+    let mut bottom = black();
+    let effects = Effects::new().with_stroke(Stroke::new(4., [1., 1., 1.]));
+    overlay_with_effects(&mut bottom, &square(), 0, 0, &effects, OverlayOptions::default());
+
+    // The edge crosses the half transparent pixels at 14 and 25, and the stroke ends 4 pixels outside.
+    for x in 11..=28 {
+        let value = bottom.get_pixel(x, 20)[0];
+        assert!((value - 1.).abs() < 1e-5, "x: {x}, value: {value}");
+    }
+    assert_eq!(bottom.get_pixel(9, 20)[0], 0.);
+    assert_eq!(bottom.get_pixel(30, 20)[0], 0.);
+}
+
+#[test]
+fn inside_stroke_stays_inside() {
+    let mut bottom = black();
+    let effects = Effects::new().with_stroke(Stroke::new(2., [1., 0., 0.]).with_position(StrokePosition::Inside));
+    overlay_with_effects(&mut bottom, &square(), 0, 0, &effects, OverlayOptions::default());
+
+    // The stroke covers 14.5 to 16.5, so pixel 16 is half red over the white layer.
+    assert_eq!(bottom.get_pixel(15, 20).0, [1., 0., 0., 1.]);
+    assert_eq!(bottom.get_pixel(16, 20).0, [1., 0.5, 0.5, 1.]);
+    assert_eq!(bottom.get_pixel(20, 20).0, [1., 1., 1., 1.]);
+    assert_eq!(bottom.get_pixel(12, 20).0, [0., 0., 0., 1.]);
+}
+
+/// Draws a single pixel of `alpha` at (4, 4) onto a white image with a black shadow, and returns the result.
+fn draw_shadow(alpha: f32, shadow: Shadow) -> Image {
+    let mut bottom = ImageBuffer::from_pixel(10, 10, Rgba([1., 1., 1., 1.]));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([1., 1., 1., alpha]));
+    let shadow = shadow.with_blend_mode(BlendMode::Normal).with_opacity(1.);
+    overlay_with_effects(&mut bottom, &top, 4, 4, &Effects::new().with_drop_shadow(shadow), OverlayOptions::default());
+    bottom
+}
+
+#[test]
+fn shadow_offset() {
+    let bottom = draw_shadow(1., Shadow::drop_shadow().with_offset(3, -2).with_blur(0.));
+
+    assert_eq!(bottom.get_pixel(7, 2).0, [0., 0., 0., 1.]);
+    assert_eq!(bottom.get_pixel(4, 4).0, [1., 1., 1., 1.]);
+    for (x, y, pixel) in bottom.enumerate_pixels() {
+        if (x, y) != (7, 2) {
+            assert_eq!(pixel.0, [1., 1., 1., 1.], "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn shadow_blur() {
+    let bottom = draw_shadow(1., Shadow::drop_shadow().with_offset(0, 0).with_blur(1.));
+
+    let sigma = 1.;
+
+    // The kernel covers 3 sigma and is normalized.
+    let weight = |i: i32| (-(i * i) as f32 / (2. * sigma * sigma)).exp();
+    let sum: f32 = (-3..=3).map(weight).sum();
+    let alpha = |dx: i32, dy: i32| weight(dx) / sum * weight(dy) / sum;
+
+    for (dx, dy) in [(1, 0), (0, 2), (2, 1), (3, 3)] {
+        let expected = 1. - alpha(dx, dy);
+        let value = bottom.get_pixel((4 + dx) as u32, (4 + dy) as u32)[0];
+        assert!((value - expected).abs() < 1e-6, "({dx}, {dy}): {value} != {expected}");
+    }
+    assert_eq!(bottom.get_pixel(8, 4).0, [1., 1., 1., 1.]);
+}
+
+/// Columns covered by the white layer.
+const LAYER: std::ops::RangeInclusive<usize> = 20..=22;
+
+/// Draws a black shadow of an opaque column from x = 20 to 22 onto white, and returns the red channel of the middle row.
+fn column_shadow(shadow: Shadow) -> Vec<f32> {
+    let mut bottom = ImageBuffer::from_pixel(50, 50, Rgba([1., 1., 1., 1.]));
+    let top = ImageBuffer::from_pixel(3, 40, Rgba([1., 1., 1., 1.]));
+    let shadow = shadow.with_offset(0, 0).with_blend_mode(BlendMode::Normal).with_opacity(1.);
+    overlay_with_effects(&mut bottom, &top, 20, 5, &Effects::new().with_drop_shadow(shadow), OverlayOptions::default());
+    (0..50).map(|x| bottom.get_pixel(x, 25)[0]).collect()
+}
+
+#[test]
+fn shadow_spread_dilates() {
+    // The blur size is 6, so the column is dilated by 6 pixels without a blur.
+    let row = column_shadow(Shadow::drop_shadow().with_blur(2.).with_spread(1.));
+    for (x, value) in row.iter().enumerate() {
+        let expected = if (14..=28).contains(&x) && !LAYER.contains(&x) { 0. } else { 1. };
+        assert!((value - expected).abs() < 1e-6, "x: {x}, value: {value}");
+    }
+}
+
+#[test]
+fn shadow_spread_with_blur() {
+    // Dilated by 3 pixels to 17..=25, and then blurred with a sigma of 1.
+    let row = column_shadow(Shadow::drop_shadow().with_blur(2.).with_spread(0.5));
+
+    let weight = |i: i32| (-(i * i) as f32 / 2.).exp();
+    let sum: f32 = (-3..=3).map(weight).sum();
+    for x in (10..40).filter(|x| !LAYER.contains(&(*x as usize))) {
+        let alpha: f32 = (-3..=3)
+            .filter(|i| (17..=25).contains(&(x + i)))
+            .map(|i| weight(i) / sum)
+            .sum();
+        let value = row[x as usize];
+        assert!((value - (1. - alpha)).abs() < 1e-5, "x: {x}, value: {value}, expected: {}", 1. - alpha);
+    }
+
+    // Without the spread, the shadow of an opaque layer starts fading at its edge.
+    let row = column_shadow(Shadow::drop_shadow().with_blur(2.));
+    assert!(row[19] > 0.1);
+}
+
+/// Draws the effects of a pixel at (4, 4), and returns the result for the options of the layer.
+fn draw_effects_with(effects: &Effects, options: OverlayOptions) -> Image {
+    let mut bottom = ImageBuffer::from_pixel(10, 10, Rgba([1., 1., 1., 1.]));
+    let top = ImageBuffer::from_pixel(1, 1, Rgba([0.5, 0.5, 0.5, 1.]));
+    overlay_with_effects(&mut bottom, &top, 4, 4, effects, options);
+    bottom
+}
+
+/// Checks that the compositing options of the layer only change the pixel of the layer.
+fn assert_ignores_layer_compositing(effects: &Effects) {
+    let expected = draw_effects_with(effects, OverlayOptions::default());
+
+    let blend_if = BlendIf::new(BlendIfChannel::Gray).with_this_layer(BlendIfRange::new(0.9, 1.));
+    let layers = [
+        OverlayOptions::default().with_composite_operator(CompositeOperator::Copy),
+        OverlayOptions::default().with_composite_operator(CompositeOperator::Clear),
+        OverlayOptions::default().with_blend_if(blend_if),
+        OverlayOptions::default().with_channel_mask(ChannelMask::RED),
+    ];
+
+    for options in layers {
+        let bottom = draw_effects_with(effects, options);
+        for (x, y, pixel) in bottom.enumerate_pixels().filter(|(x, y, _)| (*x, *y) != (4, 4)) {
+            assert_eq!(pixel, expected.get_pixel(x, y), "{options:?}, ({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn shadow_ignores_layer_compositing() {
+    let shadow = Shadow::drop_shadow().with_offset(2, 2).with_blur(1.);
+    assert_ignores_layer_compositing(&Effects::new().with_drop_shadow(shadow));
+}
+
+#[test]
+fn stroke_ignores_layer_compositing() {
+    for position in [StrokePosition::Outside, StrokePosition::Center] {
+        let stroke = Stroke::new(2., [0., 0., 0.]).with_position(position);
+        assert_ignores_layer_compositing(&Effects::new().with_stroke(stroke));
+    }
+}
+
+#[test]
+fn huge_sizes_are_capped() {
+    let sizes = [1e30, Float::INFINITY, Float::NAN];
+    for size in sizes {
+        let shadow = Shadow::drop_shadow().with_blur(size).with_spread(0.5);
+        draw_effects_with(&Effects::new().with_drop_shadow(shadow), OverlayOptions::default());
+        for position in [StrokePosition::Inside, StrokePosition::Outside, StrokePosition::Center] {
+            let stroke = Stroke::new(size, [0., 0., 0.]).with_position(position);
+            draw_effects_with(&Effects::new().with_stroke(stroke), OverlayOptions::default());
+        }
+    }
+
+    // An infinite width is as wide as the largest one.
+    let draw_stroke = |width| {
+        let mut bottom = ImageBuffer::from_pixel(600, 1, Rgba([1., 1., 1., 1.]));
+        let top = ImageBuffer::from_pixel(1, 1, Rgba([1., 1., 1., 1.]));
+        overlay_with_effects(&mut bottom, &top, 300, 0, &Effects::new().with_stroke(Stroke::new(width, [0., 0., 0.])), OverlayOptions::default());
+        bottom
+    };
+    let stroke = draw_stroke(Float::INFINITY);
+    assert_eq!(stroke, draw_stroke(250.));
+    assert_eq!(stroke.get_pixel(50, 0).0, [0., 0., 0., 1.]);
+    assert_eq!(stroke.get_pixel(49, 0).0, [1., 1., 1., 1.]);
+}
@@ -0,0 +1,86 @@
+use image::{DynamicImage, ImageBuffer, Rgba};
+use image_overlay::{AlphaMode, BlendMode, ChannelMask, Composition, GroupBlending, Layer, LayerGroup, OverlayOptions};
+
+fn pixel(color: [f32; 4]) -> DynamicImage {
+    DynamicImage::ImageRgba32F(ImageBuffer::from_pixel(1, 1, Rgba(color)))
+}
+
+fn gray(value: f32) -> [f32; 4] {
+    [value, value, value, 1.]
+}
+
+/// A group of a gray pixel of 0.25 multiplied onto its backdrop.
+fn multiply_group(blending: GroupBlending) -> LayerGroup<'static> {
+    LayerGroup::new(blending).with_layers(vec![
+        Layer::new(pixel(gray(0.25))).with_blend_mode(BlendMode::Multiply),
+    ])
+}
+
+/// Flattens the group onto gray 0.5 and returns the pixel.
+fn flatten(group: Layer) -> [f32; 4] {
+    let mut composition = Composition::new(1, 1).with_background(Rgba(gray(0.5)));
+    composition.push(group);
+    composition.flatten().get_pixel(0, 0).0
+}
+
+#[test]
+fn isolated_and_pass_through() {
+    // The isolated group multiplies onto transparency, so the child is drawn as it is.
+    assert_eq!(flatten(Layer::group(multiply_group(GroupBlending::Isolated))), gray(0.25));
+    assert_eq!(flatten(Layer::group(multiply_group(GroupBlending::PassThrough))), gray(0.125));
+}
+
+#[test]
+fn group_opacity() {
+    let isolated = Layer::group(multiply_group(GroupBlending::Isolated)).with_opacity(0.5);
+    assert_eq!(flatten(isolated), gray(0.375));
+
+    let pass_through = Layer::group(multiply_group(GroupBlending::PassThrough)).with_opacity(0.5);
+    assert_eq!(flatten(pass_through), gray(0.3125));
+
+    let hidden = Layer::group(multiply_group(GroupBlending::PassThrough)).with_opacity(0.);
+    assert_eq!(flatten(hidden), gray(0.5));
+}
+
+#[test]
+fn group_blend_mode() {
+    // Screen of 0.25 onto 0.5.
+    let isolated = Layer::group(multiply_group(GroupBlending::Isolated)).with_blend_mode(BlendMode::Screen);
+    assert_eq!(flatten(isolated), gray(0.625));
+
+    let options = OverlayOptions::new(BlendMode::Screen).with_channel_mask(ChannelMask::RED);
+    let pass_through = Layer::group(multiply_group(GroupBlending::PassThrough)).with_options(options);
+    assert_eq!(flatten(pass_through), gray(0.125));
+}
+
+#[test]
+fn isolated_result_is_straight() {
+    let group = || LayerGroup::new(GroupBlending::Isolated).with_layers(vec![
+        Layer::new(pixel([1., 1., 1., 0.5])),
+    ]);
+
+    let straight = flatten(Layer::group(group()));
+    assert_eq!(straight, gray(0.75));
+
+    let options = OverlayOptions::default().with_alpha_mode(AlphaMode::Premultiplied);
+    assert_eq!(flatten(Layer::group(group()).with_options(options)), straight);
+}
+
+#[test]
+fn nested_groups() {
+    let inner = LayerGroup::new(GroupBlending::Isolated).with_layers(vec![
+        Layer::new(pixel(gray(1.))).with_offset(1, 0),
+        Layer::new(pixel(gray(0.5))).with_offset(2, 0).with_blend_mode(BlendMode::Multiply),
+    ]);
+    let outer = LayerGroup::new(GroupBlending::PassThrough).with_layers(vec![
+        Layer::group(inner).with_offset(1, 0),
+    ]);
+
+    let mut composition = Composition::new(5, 1).with_background(Rgba(gray(0.)));
+    composition.push(Layer::group(outer).with_offset(1, 0));
+    let image = composition.flatten();
+
+    // The offsets add up, and the multiplied pixel is isolated from the black backdrop.
+    let values: Vec<f32> = image.pixels().map(|pixel| pixel[0]).collect();
+    assert_eq!(values, [0., 0., 0., 1., 0.5]);
+}
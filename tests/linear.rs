@@ -0,0 +1,50 @@
+use image::{ImageBuffer, Pixel, Rgba};
+use image_overlay::{overlay_with_options, AsRgba, BlendMode, OverlayOptions};
+
+fn overlay_pixel<P: Pixel + AsRgba>(bottom: P, top: P, options: OverlayOptions) -> P {
+    let mut bottom = ImageBuffer::from_pixel(1, 1, bottom);
+    let top = ImageBuffer::from_pixel(1, 1, top);
+    overlay_with_options(&mut bottom, &top, 0, 0, options);
+    *bottom.get_pixel(0, 0)
+}
+
+fn half_gray(linear: bool) -> OverlayOptions {
+    OverlayOptions::new(BlendMode::Normal)
+        .with_opacity(0.5)
+        .with_blend_in_linear(linear)
+}
+
+#[test]
+fn half_gray_over_black() {
+    let (black, gray) = (Rgba([0f32, 0., 0., 1.]), Rgba([0.5, 0.5, 0.5, 1.]));
+
+    // sRGB 0.5 is 0.2140 in linear light, so half of it is 0.1070, which is 0.3608 in sRGB.
+    let linear = overlay_pixel(black, gray, half_gray(true));
+    for c in &linear.0[..3] {
+        assert!((c - 0.360_780_2).abs() < 1e-5, "{c}");
+    }
+    assert_eq!(linear[3], 1.);
+
+    let gamma = overlay_pixel(black, gray, half_gray(false));
+    assert_eq!(gamma.0, [0.25, 0.25, 0.25, 1.]);
+}
+
+#[test]
+fn half_gray_over_white() {
+    let linear = overlay_pixel(Rgba([1f32, 1., 1., 1.]), Rgba([0.5, 0.5, 0.5, 1.]), half_gray(true));
+    for c in &linear.0[..3] {
+        assert!((c - 0.801_881_1).abs() < 1e-5, "{c}");
+    }
+}
+
+#[test]
+fn half_gray_over_black_u8() {
+    let linear = overlay_pixel(Rgba([0u8, 0, 0, 255]), Rgba([128, 128, 128, 255]), half_gray(true));
+    assert_eq!(linear.0, [92, 92, 92, 255]);
+}
+
+#[test]
+fn alpha_is_not_encoded() {
+    let linear = overlay_pixel(Rgba([0f32, 0., 0., 0.5]), Rgba([0.5, 0.5, 0.5, 0.5]), half_gray(true));
+    assert_eq!(linear[3], 0.625);
+}
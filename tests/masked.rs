@@ -0,0 +1,70 @@
+use image::{DynamicImage, ImageBuffer, Luma, LumaA, Rgba};
+use image_overlay::{overlay_masked, try_overlay_masked_dyn_img, BlendMode, OverlayError};
+
+type Image = ImageBuffer<Rgba<f32>, Vec<f32>>;
+
+fn black() -> Image {
+    ImageBuffer::from_pixel(4, 1, Rgba([0., 0., 0., 1.]))
+}
+
+fn white() -> Image {
+    ImageBuffer::from_pixel(4, 1, Rgba([1., 1., 1., 1.]))
+}
+
+#[test]
+fn mask_scales_coverage() {
+    let mut bottom = black();
+    let mask = ImageBuffer::from_fn(4, 1, |x, _| Luma([[0., 0.25, 0.5, 1.][x as usize]]));
+    overlay_masked(&mut bottom, &white(), 0, 0, &mask, 0, 0, BlendMode::Normal);
+
+    let values: Vec<f32> = bottom.pixels().map(|pixel| pixel[0]).collect();
+    assert_eq!(values, [0., 0.25, 0.5, 1.]);
+    assert!(bottom.pixels().all(|pixel| pixel[3] == 1.));
+}
+
+#[test]
+fn mask_alpha_scales_coverage() {
+    let mut bottom = black();
+    let mask = ImageBuffer::from_pixel(4, 1, LumaA([0.5, 0.5]));
+    overlay_masked(&mut bottom, &white(), 0, 0, &mask, 0, 0, BlendMode::Normal);
+
+    assert!(bottom.pixels().all(|pixel| pixel.0 == [0.25, 0.25, 0.25, 1.]));
+}
+
+#[test]
+fn outside_of_mask_is_not_drawn() {
+    let mut bottom = black();
+    let mask = ImageBuffer::from_pixel(2, 1, Luma([1.]));
+
+    // The mask covers the top pixels 1 and 2, which are drawn at 2 and 3.
+    overlay_masked(&mut bottom, &white(), 1, 0, &mask, 1, 0, BlendMode::Normal);
+
+    let values: Vec<f32> = bottom.pixels().map(|pixel| pixel[0]).collect();
+    assert_eq!(values, [0., 0., 1., 1.]);
+}
+
+#[test]
+fn dyn_img_masks_of_every_color_type() {
+    let mask = ImageBuffer::from_pixel(4, 1, Rgba([1., 1., 1., 1.]));
+    let masks = [
+        DynamicImage::ImageRgba32F(mask.clone()),
+        DynamicImage::ImageRgb32F(DynamicImage::ImageRgba32F(mask.clone()).to_rgb32f()),
+        DynamicImage::ImageLuma8(DynamicImage::ImageRgba32F(mask.clone()).to_luma8()),
+        DynamicImage::ImageLumaA16(DynamicImage::ImageRgba32F(mask.clone()).to_luma_alpha16()),
+        DynamicImage::ImageRgba16(DynamicImage::ImageRgba32F(mask).to_rgba16()),
+    ];
+
+    for mask in masks {
+        let mut bottom = DynamicImage::ImageRgba32F(black());
+        let result = try_overlay_masked_dyn_img(&mut bottom, &DynamicImage::ImageRgba32F(white()), 0, 0, &mask, 0, 0, BlendMode::Normal);
+
+        assert_eq!(result, Ok::<(), OverlayError>(()), "{:?}", mask.color());
+        assert_eq!(bottom.as_rgba32f().unwrap(), &white(), "{:?}", mask.color());
+    }
+}
+
+#[test]
+fn error_names_the_bottom_image() {
+    let error = OverlayError::UnsupportedColorType(image::ColorType::Rgba8);
+    assert_eq!(error.to_string(), "unsupported color type of the bottom image: Rgba8");
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "rayon")]
+
+use image::{DynamicImage, ImageBuffer, Rgba};
+use image_overlay::{overlay_dyn_img_par_with_options, overlay_dyn_img_with_options, overlay_par_with_options, overlay_with_options, BlendMode, OverlayOptions};
+
+type Image = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+fn modes() -> Vec<BlendMode> {
+    vec![
+        BlendMode::Normal,
+        #[cfg(feature = "blend_dissolve")]
+        BlendMode::Dissolve,
+        BlendMode::Multiply,
+        BlendMode::Overlay,
+        BlendMode::Difference,
+        BlendMode::Hue,
+    ]
+}
+
+fn images() -> (Image, Image) {
+    let bottom = ImageBuffer::from_fn(41, 33, |x, y| Rgba([
+        (x * 5) as u8,
+        (y * 7) as u8,
+        (x * y) as u8,
+        [255, 128, 30][((x + y) % 3) as usize],
+    ]));
+    let top = ImageBuffer::from_fn(29, 37, |x, y| Rgba([
+        (255 - y * 6) as u8,
+        (x * 9) as u8,
+        (x + y) as u8,
+        [255, 0, 200, 90][(x % 4) as usize],
+    ]));
+    (bottom, top)
+}
+
+#[test]
+fn par_is_the_same_as_sequential() {
+    let (bottom, top) = images();
+
+    for mode in modes() {
+        for opacity in [1., 0.6] {
+            let options = OverlayOptions::new(mode).with_opacity(opacity);
+
+            // The top image is clipped on every side, so rows start in the middle of it.
+            for (x, y) in [(0, 0), (6, 4), (-5, -7), (20, 10)] {
+                let mut parallel = bottom.clone();
+                overlay_par_with_options(&mut parallel, &top, x, y, options);
+
+                let mut sequential = bottom.clone();
+                overlay_with_options(&mut sequential, &top, x, y, options);
+
+                assert_eq!(parallel, sequential, "{mode}, opacity: {opacity}, ({x}, {y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn dyn_img_par_is_the_same_as_sequential() {
+    let (bottom, top) = images();
+    let bottom = DynamicImage::ImageRgba32F(DynamicImage::ImageRgba8(bottom).into_rgba32f());
+    let top = DynamicImage::ImageRgba8(top);
+
+    for mode in modes() {
+        let options = OverlayOptions::new(mode).with_opacity(0.6);
+
+        let mut parallel = bottom.clone();
+        overlay_dyn_img_par_with_options(&mut parallel, &top, -3, 5, options);
+
+        let mut sequential = bottom.clone();
+        overlay_dyn_img_with_options(&mut sequential, &top, -3, 5, options);
+
+        assert_eq!(parallel, sequential, "{mode}");
+    }
+}
+
+/// Dissolve keeps some pixels of the bottom image and replaces others.
+#[cfg(feature = "blend_dissolve")]
+#[test]
+fn par_dissolve_is_random() {
+    let (bottom, top) = images();
+    let top: Image = ImageBuffer::from_pixel(top.width(), top.height(), Rgba([255, 255, 255, 255]));
+
+    let mut parallel = bottom.clone();
+    overlay_par_with_options(&mut parallel, &top, 0, 0, OverlayOptions::new(BlendMode::Dissolve).with_opacity(0.5));
+
+    let pixels = || parallel.enumerate_pixels().filter(|(x, y, _)| *x < top.width() && *y < top.height());
+    assert!(pixels().any(|(x, y, pixel)| pixel == bottom.get_pixel(x, y)));
+    assert!(pixels().any(|(_, _, pixel)| pixel.0 == [255, 255, 255, 255]));
+}
@@ -0,0 +1,79 @@
+use image::{ImageBuffer, Luma};
+use image_overlay::{overlay_placed, Anchor, BlendMode, Placement};
+
+const ANCHORS: [Anchor; 9] = [
+    Anchor::TopLeft,
+    Anchor::Top,
+    Anchor::TopRight,
+    Anchor::Left,
+    Anchor::Center,
+    Anchor::Right,
+    Anchor::BottomLeft,
+    Anchor::Bottom,
+    Anchor::BottomRight,
+];
+
+#[test]
+fn anchors_with_margin() {
+    let expected = [
+        (16, 8), (45, 8), (74, 8), 
+        (16, 20), (45, 20), (74, 20), 
+        (16, 32), (45, 32), (74, 32),
+    ];
+
+    for (anchor, expected) in ANCHORS.into_iter().zip(expected) {
+        let placement = Placement::new(anchor).with_margin(16, 8);
+        assert_eq!(placement.position((100, 60), (10, 20)), expected, "{anchor:?}");
+    }
+}
+
+#[test]
+fn negative_margin_moves_outward() {
+    let top_left = Placement::new(Anchor::TopLeft).with_margin(-5, -3);
+    assert_eq!(top_left.position((100, 60), (10, 20)), (-5, -3));
+
+    let bottom_right = Placement::new(Anchor::BottomRight).with_margin(-5, -3);
+    assert_eq!(bottom_right.position((100, 60), (10, 20)), (95, 43));
+}
+
+#[test]
+fn center_rounds_to_top_left() {
+    // The remaining space is 91 x 51, so half a pixel is dropped on each axis.
+    let center = Placement::new(Anchor::Center);
+    assert_eq!(center.position((101, 61), (10, 10)), (45, 25));
+    assert_eq!(center.position((100, 60), (10, 10)), (45, 25));
+}
+
+#[test]
+fn top_larger_than_bottom() {
+    // The remaining space is -5 and -4, and -5 / 2 is rounded down to -3 as with odd positive space.
+    let expected = [
+        (0, 0), (-3, 0), (-5, 0), 
+        (0, -2), (-3, -2), (-5, -2), 
+        (0, -4), (-3, -4), (-5, -4),
+    ];
+
+    for (anchor, expected) in ANCHORS.into_iter().zip(expected) {
+        assert_eq!(Placement::new(anchor).position((10, 10), (15, 14)), expected, "{anchor:?}");
+    }
+}
+
+#[test]
+fn percent_offset() {
+    let placement = Placement::new(Anchor::TopLeft).with_percent(-10., 25.);
+    assert_eq!(placement.position((200, 60), (10, 10)), (-20, 15));
+
+    // 0.5% of 101 is 0.505, which is rounded to the nearest pixel.
+    let placement = Placement::new(Anchor::Center).with_percent(0.5, -0.5);
+    assert_eq!(placement.position((101, 101), (11, 11)), (46, 44));
+}
+
+#[test]
+fn larger_top_is_clipped() {
+    let mut bottom = ImageBuffer::from_pixel(4, 1, Luma([0u8]));
+    let top = ImageBuffer::from_fn(9, 1, |x, _| Luma([x as u8 * 10]));
+
+    // The top is placed at -3 (not -2), so its pixels 3 to 6 are drawn.
+    overlay_placed(&mut bottom, &top, Placement::new(Anchor::Center), BlendMode::Normal);
+    assert_eq!(bottom.into_raw(), [30, 40, 50, 60]);
+}
@@ -0,0 +1,87 @@
+use image::{DynamicImage, ImageBuffer, Rgba};
+use image_overlay::{overlay_buffer_with_options, overlay_dyn_img_with_options, overlay_rgba8_sse2_with_options, overlay_rgba8_with_options, BlendMode, Float, OverlayOptions};
+
+type Image = ImageBuffer<Rgba<u8>, Vec<u8>>;
+
+const MODES: [BlendMode; 9] = [
+    BlendMode::Normal,
+    BlendMode::Multiply,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Darken,
+    BlendMode::Lighten,
+    BlendMode::Difference,
+    BlendMode::LinearDodge,
+    BlendMode::Subtract,
+];
+
+const OPACITIES: [Float; 5] = [1., 0.75, 0.5, 0.1, 0.];
+
+/// The width is not a multiple of 8, so the last pixels of each row are blended in padded buffers.
+fn images() -> (Image, Image) {
+    let bottom = ImageBuffer::from_fn(37, 19, |x, y| Rgba([
+        (x * 7) as u8, 
+        (y * 13) as u8, 
+        (x * y) as u8, 
+        [0, 255, 128, 17][(x % 4) as usize],
+    ]));
+    let top = ImageBuffer::from_fn(37, 19, |x, y| Rgba([
+        (255 - x * 5) as u8, 
+        (x * 3 + y * 11) as u8, 
+        128, 
+        [255, 0, 64, 200, 1][((x + y) % 5) as usize],
+    ]));
+    (bottom, top)
+}
+
+/// Checks that the vectorized `overlay` is within ±1 of the scalar path.
+fn assert_within_one_of_scalar(overlay: fn(&mut Image, &Image, i64, i64, OverlayOptions)) {
+    let (bottom, top) = images();
+
+    for mode in MODES {
+        for opacity in OPACITIES {
+            let options = OverlayOptions::new(mode).with_opacity(opacity);
+            for (x, y) in [(0, 0), (5, -3), (-11, 7)] {
+                let mut simd = bottom.clone();
+                overlay(&mut simd, &top, x, y, options);
+
+                let mut scalar = bottom.clone();
+                overlay_buffer_with_options(&mut scalar, &top, x, y, options);
+
+                for ((px, py, a), b) in simd.enumerate_pixels().zip(scalar.pixels()) {
+                    for (a, b) in a.0.iter().zip(b.0) {
+                        assert!(a.abs_diff(b) <= 1, "{mode}, opacity: {opacity}, ({px}, {py}): {a} != {b}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rgba8_is_within_one_of_scalar() {
+    assert_within_one_of_scalar(overlay_rgba8_with_options);
+}
+
+/// AVX2 is selected whenever it is available, so SSE2 is forced to be tested too.
+#[test]
+fn sse2_is_within_one_of_scalar() {
+    assert_within_one_of_scalar(overlay_rgba8_sse2_with_options);
+}
+
+#[test]
+fn dyn_img_is_the_same_as_scalar() {
+    let (bottom, top) = images();
+
+    for mode in MODES {
+        let options = OverlayOptions::new(mode).with_opacity(0.75);
+
+        let mut dyn_bottom = DynamicImage::ImageRgba8(bottom.clone());
+        overlay_dyn_img_with_options(&mut dyn_bottom, &DynamicImage::ImageRgba8(top.clone()), 3, 2, options);
+
+        let mut scalar = bottom.clone();
+        overlay_buffer_with_options(&mut scalar, &top, 3, 2, options);
+
+        assert_eq!(dyn_bottom.as_rgba8().unwrap(), &scalar, "{mode}");
+    }
+}
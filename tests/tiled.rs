@@ -0,0 +1,68 @@
+use image::{ImageBuffer, Luma};
+use image_overlay::{overlay_tiled, BlendMode, Tiling, WrapMode};
+
+type Image = ImageBuffer<Luma<u8>, Vec<u8>>;
+
+fn pattern() -> Image {
+    ImageBuffer::from_raw(3, 1, vec![10, 20, 30]).unwrap()
+}
+
+/// Fills a row of 8 pixels from `x`.
+fn tile_row(x: i64, wrap: WrapMode, phase: i64) -> Vec<u8> {
+    let mut bottom = Image::new(8, 1);
+    let tiling = Tiling::new(wrap, WrapMode::Repeat).with_phase(phase, 0);
+    overlay_tiled(&mut bottom, &pattern(), x, 0, 8, 1, tiling, BlendMode::Normal);
+    bottom.into_raw()
+}
+
+#[test]
+fn repeat() {
+    assert_eq!(tile_row(0, WrapMode::Repeat, 0), [10, 20, 30, 10, 20, 30, 10, 20]);
+    assert_eq!(tile_row(0, WrapMode::Repeat, 1), [20, 30, 10, 20, 30, 10, 20, 30]);
+    assert_eq!(tile_row(0, WrapMode::Repeat, -1), [30, 10, 20, 30, 10, 20, 30, 10]);
+}
+
+#[test]
+fn mirror_repeat() {
+    assert_eq!(tile_row(0, WrapMode::MirrorRepeat, 0), [10, 20, 30, 30, 20, 10, 10, 20]);
+    assert_eq!(tile_row(0, WrapMode::MirrorRepeat, -2), [20, 10, 10, 20, 30, 30, 20, 10]);
+}
+
+#[test]
+fn once() {
+    assert_eq!(tile_row(0, WrapMode::Once, 0), [10, 20, 30, 0, 0, 0, 0, 0]);
+    assert_eq!(tile_row(0, WrapMode::Once, -2), [0, 0, 10, 20, 30, 0, 0, 0]);
+}
+
+#[test]
+fn rectangle_is_clipped() {
+    // The rectangle covers -1 to 6, and the pattern starts at its left edge.
+    assert_eq!(tile_row(-1, WrapMode::Repeat, 0), [20, 30, 10, 20, 30, 10, 20, 0]);
+    assert_eq!(tile_row(3, WrapMode::Repeat, 0), [0, 0, 0, 10, 20, 30, 10, 20]);
+}
+
+#[test]
+fn axes_wrap_independently() {
+    let top = Image::from_raw(2, 2, vec![1, 2, 3, 4]).unwrap();
+    let mut bottom = Image::new(5, 3);
+    let tiling = Tiling::new(WrapMode::MirrorRepeat, WrapMode::Once);
+    overlay_tiled(&mut bottom, &top, 0, 0, 5, 3, tiling, BlendMode::Normal);
+
+    assert_eq!(bottom.into_raw(), [
+        1, 2, 2, 1, 1, 
+        3, 4, 4, 3, 3, 
+        0, 0, 0, 0, 0,
+    ]);
+}
+
+#[test]
+fn huge_phases() {
+    // i64::MAX is 1 more than a multiple of 6, and i64::MIN is 2 less.
+    assert_eq!(tile_row(0, WrapMode::Repeat, i64::MAX), tile_row(0, WrapMode::Repeat, 1));
+    assert_eq!(tile_row(0, WrapMode::Repeat, i64::MIN), tile_row(0, WrapMode::Repeat, -2));
+    assert_eq!(tile_row(0, WrapMode::MirrorRepeat, i64::MAX), tile_row(0, WrapMode::MirrorRepeat, 1));
+    assert_eq!(tile_row(0, WrapMode::MirrorRepeat, i64::MIN), tile_row(0, WrapMode::MirrorRepeat, -2));
+    assert_eq!(tile_row(0, WrapMode::Once, i64::MAX), [0; 8]);
+    assert_eq!(tile_row(0, WrapMode::Once, i64::MIN), [0; 8]);
+    assert_eq!(tile_row(-5, WrapMode::Repeat, i64::MAX), tile_row(-5, WrapMode::Repeat, 1));
+}
@@ -0,0 +1,116 @@
+use image::{ImageBuffer, Rgba};
+use image_overlay::{overlay, overlay_transformed, overlay_transformed_with_options, Affine, BlendMode, Interpolation, OverlayOptions};
+
+type Image = ImageBuffer<Rgba<f32>, Vec<f32>>;
+
+const INTERPOLATIONS: [Interpolation; 3] = [
+    Interpolation::Nearest,
+    Interpolation::Bilinear,
+    Interpolation::Bicubic,
+];
+
+fn gradient() -> Image {
+    ImageBuffer::from_fn(6, 4, |x, y| Rgba([x as f32 / 8., y as f32 / 4., 0.5, 1.]))
+}
+
+fn black() -> Image {
+    ImageBuffer::from_pixel(12, 8, Rgba([0., 0., 0., 1.]))
+}
+
+fn assert_close(a: [f32; 4], b: [f32; 4], message: &str) {
+    for (a, b) in a.iter().zip(b) {
+        assert!((a - b).abs() < 1e-6, "{message}: {a} != {b}");
+    }
+}
+
+#[test]
+fn identity_is_the_same_as_overlay() {
+    let mut expected = black();
+    overlay(&mut expected, &gradient(), 0, 0, BlendMode::Normal);
+
+    for interpolation in INTERPOLATIONS {
+        let mut bottom = black();
+        overlay_transformed(&mut bottom, &gradient(), Affine::IDENTITY, interpolation, BlendMode::Normal);
+        assert_eq!(bottom, expected, "{interpolation:?}");
+    }
+}
+
+#[test]
+fn integer_translation_is_the_same_as_overlay() {
+    let mut expected = black();
+    overlay(&mut expected, &gradient(), 3, -2, BlendMode::Normal);
+
+    for interpolation in INTERPOLATIONS {
+        let mut bottom = black();
+        overlay_transformed(&mut bottom, &gradient(), Affine::translation(3., -2.), interpolation, BlendMode::Normal);
+        assert_eq!(bottom, expected, "{interpolation:?}");
+    }
+}
+
+#[test]
+fn half_pixel_translation() {
+    let top = gradient();
+    let mut bottom = black();
+    overlay_transformed(&mut bottom, &top, Affine::translation(0.5, 0.), Interpolation::Bilinear, BlendMode::Normal);
+
+    // Each pixel is the average of two neighbors, and the edge pixels are half covered.
+    for x in 1..6 {
+        let expected = (top.get_pixel(x - 1, 1)[0] + top.get_pixel(x, 1)[0]) / 2.;
+        assert_close(bottom.get_pixel(x, 1).0, [expected, 0.25, 0.5, 1.], &format!("x: {x}"));
+    }
+    assert_close(bottom.get_pixel(0, 1).0, [0., 0.125, 0.25, 1.], "left edge");
+    assert_close(bottom.get_pixel(6, 1).0, [5. / 16., 0.125, 0.25, 1.], "right edge");
+    assert_eq!(bottom.get_pixel(7, 1).0, [0., 0., 0., 1.]);
+}
+
+#[test]
+fn hdr_keeps_colors_above_one() {
+    // A ramp in red, and a step from 0 to 4 in green.
+    let top: Image = ImageBuffer::from_fn(6, 4, |x, _| Rgba([x as f32, if x < 3 { 0. } else { 4. }, 0.5, 1.]));
+    let options = OverlayOptions::new(BlendMode::Normal).with_hdr(true);
+
+    for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+        let mut bottom = black();
+        overlay_transformed_with_options(&mut bottom, &top, Affine::translation(0.5, 0.), interpolation, options);
+
+        // Both filters reproduce a ramp away from the edges.
+        for x in 2..5 {
+            let [r, _, b, a] = bottom.get_pixel(x, 1).0;
+            assert_close([r, b, a, 0.], [x as f32 - 0.5, 0.5, 1., 0.], &format!("{interpolation:?}, x: {x}"));
+        }
+
+        // The overshoot of bicubic around the step is clamped only below 0.
+        let greens: Vec<f32> = (1..6).map(|x| bottom.get_pixel(x, 1)[1]).collect();
+        assert!(greens.iter().all(|g| *g >= 0.), "{interpolation:?}: {greens:?}");
+        assert_eq!(greens[2], 2., "{interpolation:?}");
+        if interpolation == Interpolation::Bicubic {
+            assert!(greens[3] > 4., "{greens:?}");
+        }
+    }
+}
+
+#[test]
+fn nearest_scaling() {
+    let top = gradient();
+    let mut bottom = black();
+    overlay_transformed(&mut bottom, &top, Affine::scaling(2., 2.), Interpolation::Nearest, BlendMode::Normal);
+
+    for (x, y, pixel) in bottom.enumerate_pixels() {
+        assert_eq!(pixel, top.get_pixel(x / 2, y / 2), "({x}, {y})");
+    }
+}
+
+#[test]
+fn not_invertible_draws_nothing() {
+    let mut bottom = black();
+    overlay_transformed(&mut bottom, &gradient(), Affine::scaling(0., 1.), Interpolation::Bilinear, BlendMode::Normal);
+    assert_eq!(bottom, black());
+}
+
+#[test]
+fn affine_then_and_inverse() {
+    let transform = Affine::scaling(2., 4.).then(Affine::translation(3., -1.));
+    assert_eq!(transform.apply(1., 1.), (5., 3.));
+    assert_eq!(transform.inverse().unwrap().apply(5., 3.), (1., 1.));
+    assert_eq!(Affine::scaling(0., 1.).inverse(), None);
+}